@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrElement;
+use tauvslagrange::{
+    prover::Prover,
+    srs::generate_srs,
+    utils::{lagrange_srs_downsample, random_poly},
+};
+
+const MIN_LOG_SIZE: u32 = 10;
+const MAX_LOG_SIZE: u32 = 16;
+
+fn bench_commit(c: &mut Criterion) {
+    // one SRS for the largest domain (degree 2^16 - 1 under blowup 2), shared by all sizes
+    let srs = generate_srs(2 << MAX_LOG_SIZE, FrElement::from(42));
+
+    let mut group = c.benchmark_group("commit");
+    group.sample_size(10);
+
+    for log_size in MIN_LOG_SIZE..=MAX_LOG_SIZE {
+        let n = 1 << log_size;
+        let lagrange_srs = lagrange_srs_downsample(&srs, log_size + 1).unwrap();
+        let prover = Prover::new(random_poly(n - 1)).unwrap();
+        let witness = random_poly(n - 1);
+
+        group.bench_with_input(BenchmarkId::new("lagrange", n), &n, |b, _| {
+            b.iter(|| prover.commit_lagrange(&witness, &lagrange_srs).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("powers_of_tau", n), &n, |b, _| {
+            b.iter(|| prover.commit_polynomial(&witness, &srs).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_commit);
+criterion_main!(benches);
@@ -1,7 +1,8 @@
+use lambdaworks_math::{fft::polynomial::FFTPoly, msm::naive::msm};
 use tauvslagrange::{
     prover::Prover,
-    serialize::SerializedSRS,
-    srs::generate_srs,
+    serialize::{SerializedG2SRS, SerializedSRS},
+    srs::{estimated_bytes, for_degree, generate_srs, generate_srs_g2},
     utils::{random_fr, random_poly, to_lagrange_basis},
 };
 
@@ -17,7 +18,81 @@ macro_rules! time_it {
     }};
 }
 
+#[macro_export]
+macro_rules! time_it_repeated {
+    ($label:expr, $iters:expr, $block:expr) => {{
+        let iters: usize = $iters;
+        assert!(iters > 0, "{} needs at least one run", $label);
+        println!("{} ({} runs) ...", $label, iters);
+        let mut times = Vec::with_capacity(iters);
+        let mut result = None;
+        for _ in 0..iters {
+            let start = std::time::Instant::now();
+            result = Some($block);
+            times.push(start.elapsed());
+        }
+        times.sort();
+        let median = (times[(iters - 1) / 2] + times[iters / 2]) / 2;
+        let mean = times.iter().sum::<std::time::Duration>() / iters as u32;
+        println!(
+            "{} - Min: {:?}, Median: {:?}, Mean: {:?}",
+            $label, times[0], median, mean
+        );
+        result.unwrap()
+    }};
+}
+
+/// Runs per commitment in the tau vs Lagrange comparison, see [`time_it_repeated`]
+const TIMING_ITERS: usize = 3;
+
+/// Blowup the prover evaluates with; the SRS size follows from it via [`srs_size`]
+const BLOWUP: usize = 2;
+
+/// SRS length for committing products of polynomials with `n` coefficients under
+/// `blowup`, shared by SRS generation and the commit path
+fn srs_size(n: usize, blowup: usize) -> usize {
+    for_degree(n - 1, blowup)
+}
+
+/// Commit a small random polynomial both ways on a fresh SRS and compare the results
+fn selftest() -> Result<bool, Box<dyn std::error::Error>> {
+    let n = 8;
+    let srs = generate_srs(srs_size(n, BLOWUP), random_fr());
+    let lagrange_srs = to_lagrange_basis(&srs)?;
+
+    // plain coefficient vs evaluation commitment, as in the to_lagrange_basis test
+    let polynomial = random_poly(srs.len() - 1);
+    let cs = polynomial
+        .coefficients()
+        .iter()
+        .map(|c| c.representative())
+        .collect::<Vec<_>>();
+    let evaluations = polynomial
+        .evaluate_fft(1, None)?
+        .iter()
+        .map(|e| e.representative())
+        .collect::<Vec<_>>();
+    let basis_ok = msm(&cs, &srs)? == msm(&evaluations, &lagrange_srs)?;
+
+    // product commitment through the prover
+    let prover = Prover::new_with_blowup(random_poly(n - 1), BLOWUP)?;
+    let witness = random_poly(n - 1);
+    let prover_ok = prover.commit_polynomial(&witness, &srs)?
+        == prover.commit_lagrange(&witness, &lagrange_srs)?;
+
+    Ok(basis_ok && prover_ok)
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == "--selftest") {
+        let passed = time_it!("Self-test", selftest()?);
+        println!("{}", if passed { "PASS" } else { "FAIL" });
+        if !passed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     println!("*******************************");
     println!("*                             *");
     println!("*  Powers of Tau vs Lagrange  *");
@@ -39,8 +114,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Ok(line) => match line.trim() {
                 "1" => {
                     println!("\n\n------------ Setup ------------");
-                    let tau_srs =
-                        time_it!("Loading powers of tau", SerializedSRS::load("srs.json")?);
+                    // the G2 SRS is optional: setups generated before it was written lack it
+                    let g2 = if std::path::Path::new("srs_g2.json").exists() {
+                        Some(SerializedG2SRS::load("srs_g2.json")?.to_ec_points()?)
+                    } else {
+                        None
+                    };
+                    let (tau_points, paired) = time_it!(
+                        "Loading powers of tau",
+                        SerializedSRS::load_paired("srs.json", g2.as_deref())?
+                    );
+                    if paired == Some(false) {
+                        eprintln!("\n!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!");
+                        eprintln!("WARNING: srs.json and srs_g2.json encode different taus.");
+                        eprintln!("They come from different setups; regenerate them together.");
+                        eprintln!("!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!\n");
+                    }
                     let lagrange_srs = time_it!(
                         "Loading powers of tau in Lagrange basis",
                         SerializedSRS::load("lagrange_srs.json")?
@@ -48,39 +137,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     // generate a random polynomial of degree n-1
                     let poly = time_it!("Polynomial Generation", { random_poly(n - 1) });
-                    let prover = Prover::new(poly)?;
+                    let prover = Prover::new_with_blowup(poly, BLOWUP)?;
 
                     println!("\n\n------------ Prover ------------");
                     let witness = time_it!("Witness Generation", random_poly(n - 1));
-                    let commitment1 = time_it!("Commitment Calculation (Powers of Tau)", {
-                        prover.commit_polynomial(&witness, tau_srs.to_ec_points().as_slice())
-                    })?;
+                    // decode once so the repeated runs time the commitments alone
+                    let lagrange_points = lagrange_srs.to_ec_points();
+                    let commitment1 = time_it_repeated!(
+                        "Commitment Calculation (Powers of Tau)",
+                        TIMING_ITERS,
+                        prover.commit_polynomial(&witness, &tau_points)
+                    )?;
 
-                    let commitment2 = time_it!("Commitment Calculation (Lagrange)", {
-                        prover.commit_lagrange(&witness, &lagrange_srs.to_ec_points().as_slice())
-                    })?;
+                    let commitment2 = time_it_repeated!(
+                        "Commitment Calculation (Lagrange)",
+                        TIMING_ITERS,
+                        prover.commit_lagrange_affine(&witness, &lagrange_points)
+                    )?;
 
                     println!("\n\n------------ Result ------------");
+                    let commitment1 = commitment1.to_affine();
                     println!(
                         "Commitment[t] G1: ({},{})",
-                        commitment1.to_affine().x(),
-                        commitment1.to_affine().y()
+                        commitment1.x(),
+                        commitment1.y()
                     );
                     println!(
                         "Commitment[l] G1: ({},{})",
-                        commitment2.to_affine().x(),
-                        commitment2.to_affine().y()
+                        commitment2.x(),
+                        commitment2.y()
+                    );
+                    println!(
+                        "Commitments match: {}",
+                        commitment1.x() == commitment2.x() && commitment1.y() == commitment2.y()
                     );
                 }
                 "2" => {
                     println!("\n\n------------ Setup ------------");
-                    let srs = time_it!("SRS Generation", { generate_srs(2 * n, random_fr()) });
+                    // powers of tau plus the Lagrange SRS
+                    let size = srs_size(n, BLOWUP);
+                    println!(
+                        "Estimated SRS memory: {} MiB",
+                        (2 * estimated_bytes(size)) >> 20
+                    );
+                    let tau = random_fr();
+                    let srs = time_it!("SRS Generation", { generate_srs(size, tau.clone()) });
 
                     let lagrange_srs =
                         time_it!("Lagrange SRS Generation", { to_lagrange_basis(&srs)? });
 
                     SerializedSRS::from(srs).dump("srs.json")?;
                     SerializedSRS::from(lagrange_srs).dump("lagrange_srs.json")?;
+                    SerializedG2SRS::from(generate_srs_g2(&tau).as_slice()).dump("srs_g2.json")?;
                 }
                 "3" => {
                     println!("Bye!");
@@ -104,3 +212,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srs_size() {
+        // n coefficients pad to a power of two, then scale with the blowup
+        assert_eq!(srs_size(8, 2), 16);
+        assert_eq!(srs_size(8, 4), 32);
+        assert_eq!(srs_size(5, 2), 16);
+        assert_eq!(srs_size(2_usize.pow(17), BLOWUP), 2_usize.pow(17) * BLOWUP);
+    }
+
+    #[test]
+    fn test_time_it_repeated() {
+        let mut runs = 0;
+        let result = time_it_repeated!("Counter", 5, {
+            runs += 1;
+            runs * 10
+        });
+        assert_eq!(runs, 5);
+        // the last run's value is returned
+        assert_eq!(result, 50);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{error::Error, fmt};
+
+use lambdaworks_math::{
+    elliptic_curve::short_weierstrass::curves::bls12_381::default_types::{FrElement, FrField},
+    fft::{cpu::roots_of_unity, errors::FFTError, polynomial::FFTPoly},
+    field::traits::{IsFFTField, RootsConfig},
+    polynomial::Polynomial,
+};
+
+use crate::utils::max_fft_domain_log2;
+
+#[derive(Debug)]
+pub enum DomainError {
+    OrderTooLarge(u32),
+    InvalidBlowup(usize),
+    ZeroOffset,
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DomainError::OrderTooLarge(order) => write!(
+                f,
+                "Domain of order 2^{} exceeds the field's 2-adicity {}",
+                order,
+                max_fft_domain_log2()
+            ),
+            DomainError::InvalidBlowup(blowup) => {
+                write!(f, "Blowup {} is not a power of two", blowup)
+            }
+            DomainError::ZeroOffset => write!(f, "Coset offset must be non-zero"),
+        }
+    }
+}
+
+impl Error for DomainError {}
+
+/// Evaluation domain: `2^log_size` polynomial coefficients evaluated on `blowup` times
+/// as many points, optionally shifted to the coset `offset * <w>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Domain {
+    log_size: u32,
+    blowup: usize,
+    offset: Option<FrElement>,
+}
+
+impl Domain {
+    /// Create a domain over the roots of unity, validating it against the 2-adicity
+    pub fn new(log_size: u32, blowup: usize) -> Result<Self, DomainError> {
+        if blowup == 0 || !blowup.is_power_of_two() {
+            return Err(DomainError::InvalidBlowup(blowup));
+        }
+
+        let order = log_size + blowup.trailing_zeros();
+        if order > max_fft_domain_log2() {
+            return Err(DomainError::OrderTooLarge(order));
+        }
+
+        Ok(Domain {
+            log_size,
+            blowup,
+            offset: None,
+        })
+    }
+
+    /// Create a domain over the coset `offset * <w>`
+    pub fn new_coset(log_size: u32, blowup: usize, offset: FrElement) -> Result<Self, DomainError> {
+        if offset == FrElement::zero() {
+            return Err(DomainError::ZeroOffset);
+        }
+
+        let mut domain = Domain::new(log_size, blowup)?;
+        domain.offset = Some(offset);
+        Ok(domain)
+    }
+
+    /// Smallest domain holding a polynomial with `coeff_len` coefficients
+    pub fn for_coeff_len(coeff_len: usize, blowup: usize) -> Result<Self, DomainError> {
+        Domain::new(coeff_len.next_power_of_two().trailing_zeros(), blowup)
+    }
+
+    /// Log2 of the number of polynomial coefficients the domain holds
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    pub fn blowup(&self) -> usize {
+        self.blowup
+    }
+
+    pub fn offset(&self) -> Option<&FrElement> {
+        self.offset.as_ref()
+    }
+
+    /// Number of polynomial coefficients the domain holds
+    pub fn coeff_size(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Number of evaluation points, including the blowup
+    pub fn size(&self) -> usize {
+        self.coeff_size() * self.blowup
+    }
+
+    /// Log2 of the number of evaluation points
+    pub fn order(&self) -> u32 {
+        self.size().trailing_zeros()
+    }
+
+    /// Generator `w` of the subgroup of size [`Domain::size`]
+    pub fn generator(&self) -> FrElement {
+        // the order was checked against the 2-adicity on construction
+        FrField::get_primitive_root_of_unity::<FrField>(self.order().into()).unwrap()
+    }
+
+    /// Twiddle factors for an FFT over the subgroup. The offset is left out, callers
+    /// handle a coset by prescaling
+    pub fn twiddles(&self, config: RootsConfig) -> Result<Vec<FrElement>, FFTError> {
+        roots_of_unity::get_twiddles(self.order().into(), config)
+    }
+
+    /// The i-th evaluation point, `offset * w^i`
+    pub fn element(&self, i: usize) -> FrElement {
+        let element = self.generator().pow(i as u64);
+
+        match &self.offset {
+            Some(offset) => offset * element,
+            None => element,
+        }
+    }
+
+    /// Evaluate a polynomial on the domain, in natural order
+    pub fn evaluate(&self, poly: &Polynomial<FrElement>) -> Result<Vec<FrElement>, FFTError> {
+        match &self.offset {
+            Some(offset) => poly.evaluate_offset_fft(self.blowup, Some(self.coeff_size()), offset),
+            None => poly.evaluate_fft(self.blowup, Some(self.coeff_size())),
+        }
+    }
+
+    /// Interpolate evaluations on the domain back to coefficients
+    pub fn interpolate(
+        &self,
+        evaluations: &[FrElement],
+    ) -> Result<Polynomial<FrElement>, FFTError> {
+        match &self.offset {
+            Some(offset) => Polynomial::interpolate_offset_fft(evaluations, offset),
+            None => Polynomial::interpolate_fft(evaluations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::random_poly;
+
+    use super::*;
+
+    #[test]
+    fn test_domain_validation() {
+        let domain = Domain::new(3, 2).unwrap();
+        assert_eq!(domain.size(), 16);
+        assert_eq!(domain.order(), 4);
+        assert_eq!(domain.element(1), domain.generator());
+        assert_eq!(domain.twiddles(RootsConfig::Natural).unwrap().len(), 8);
+
+        assert!(Domain::new(32, 1).is_ok());
+        assert!(matches!(
+            Domain::new(31, 4),
+            Err(DomainError::OrderTooLarge(33))
+        ));
+        assert!(matches!(
+            Domain::new(3, 3),
+            Err(DomainError::InvalidBlowup(3))
+        ));
+        assert!(matches!(
+            Domain::new_coset(3, 2, FrElement::zero()),
+            Err(DomainError::ZeroOffset)
+        ));
+    }
+
+    #[test]
+    fn test_domain_evaluate() {
+        let poly = random_poly(3);
+
+        for domain in [
+            Domain::new(2, 2).unwrap(),
+            Domain::new_coset(2, 2, FrElement::from(7)).unwrap(),
+        ] {
+            let evaluations = domain.evaluate(&poly).unwrap();
+            assert_eq!(evaluations.len(), domain.size());
+
+            for (i, e) in evaluations.iter().enumerate() {
+                assert_eq!(poly.evaluate(&domain.element(i)), *e);
+            }
+            assert_eq!(domain.interpolate(&evaluations).unwrap(), poly);
+        }
+    }
+}
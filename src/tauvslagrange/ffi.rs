@@ -0,0 +1,176 @@
+use std::{error::Error, fmt};
+
+use lambdaworks_math::{
+    elliptic_curve::short_weierstrass::curves::bls12_381::{
+        compression::{compress_g1_point, decompress_g1_point},
+        default_types::FrElement,
+    },
+    polynomial::Polynomial,
+    traits::ByteConversion,
+    unsigned_integer::element::U256,
+};
+
+use crate::{
+    prover::{Prover, ProverError},
+    utils::is_reduced,
+    G1Point,
+};
+
+/// Bytes per scalar: a big-endian field element
+const SCALAR_BYTES: usize = 32;
+/// Bytes per point: a compressed G1 point in the IETF / ZCash encoding
+const POINT_BYTES: usize = 48;
+
+#[derive(Debug)]
+pub enum CommitError {
+    /// The named input is not a whole number of items
+    InvalidLength(&'static str, usize),
+    /// The named input holds a scalar `>= r` at the given index
+    NonCanonicalScalar(&'static str, usize),
+    /// The SRS holds an undecodable or off-subgroup point at the given index
+    InvalidPoint(usize),
+    Prover(ProverError),
+}
+
+impl fmt::Display for CommitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CommitError::InvalidLength(input, len) => {
+                write!(f, "{} length {} is not a whole number of items", input, len)
+            }
+            CommitError::NonCanonicalScalar(input, i) => {
+                write!(f, "{} scalar {} is not canonically reduced", input, i)
+            }
+            CommitError::InvalidPoint(i) => write!(f, "SRS point {} is invalid", i),
+            CommitError::Prover(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for CommitError {}
+
+impl From<ProverError> for CommitError {
+    fn from(err: ProverError) -> Self {
+        CommitError::Prover(err)
+    }
+}
+
+/// Byte-in, byte-out [`Prover::commit_lagrange`] for binding from other languages.
+/// `poly_bytes` and `witness_bytes` are coefficient vectors of 32-byte big-endian
+/// scalars (lowest degree first), `srs_bytes` is the Lagrange SRS as 48-byte compressed
+/// points. Returns the commitment in the same compressed encoding
+pub fn commit_bytes(
+    poly_bytes: &[u8],
+    witness_bytes: &[u8],
+    srs_bytes: &[u8],
+) -> Result<[u8; 48], CommitError> {
+    let poly = decode_polynomial("Polynomial", poly_bytes)?;
+    let witness = decode_polynomial("Witness", witness_bytes)?;
+    let lagrange_srs = decode_points(srs_bytes)?;
+
+    let commitment = Prover::new(poly)?.commit_lagrange(&witness, &lagrange_srs)?;
+
+    let mut bytes = [0u8; POINT_BYTES];
+    bytes.copy_from_slice(&compress_g1_point(&commitment));
+    Ok(bytes)
+}
+
+fn decode_polynomial(
+    input: &'static str,
+    bytes: &[u8],
+) -> Result<Polynomial<FrElement>, CommitError> {
+    if !bytes.len().is_multiple_of(SCALAR_BYTES) {
+        return Err(CommitError::InvalidLength(input, bytes.len()));
+    }
+
+    let coefficients = bytes
+        .chunks_exact(SCALAR_BYTES)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let value = U256::from_bytes_be(chunk)
+                .map_err(|_| CommitError::InvalidLength(input, bytes.len()))?;
+            if !is_reduced(&value) {
+                return Err(CommitError::NonCanonicalScalar(input, i));
+            }
+            Ok(FrElement::from(&value))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Polynomial::new(&coefficients))
+}
+
+fn decode_points(bytes: &[u8]) -> Result<Vec<G1Point>, CommitError> {
+    if !bytes.len().is_multiple_of(POINT_BYTES) {
+        return Err(CommitError::InvalidLength("SRS", bytes.len()));
+    }
+
+    bytes
+        .chunks_exact(POINT_BYTES)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut compressed = [0u8; POINT_BYTES];
+            compressed.copy_from_slice(chunk);
+            decompress_g1_point(&mut compressed).map_err(|_| CommitError::InvalidPoint(i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        srs::generate_srs,
+        utils::{random_poly, to_lagrange_basis},
+    };
+
+    use super::*;
+
+    fn encode_polynomial(poly: &Polynomial<FrElement>) -> Vec<u8> {
+        poly.coefficients()
+            .iter()
+            .flat_map(|c| c.representative().to_bytes_be())
+            .collect()
+    }
+
+    #[test]
+    fn test_commit_bytes() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let srs_bytes = lagrange_srs
+            .iter()
+            .flat_map(compress_g1_point)
+            .collect::<Vec<_>>();
+        let poly = random_poly(7);
+        let witness = random_poly(7);
+
+        let expected = Prover::new(poly.clone())
+            .unwrap()
+            .commit_lagrange(&witness, &lagrange_srs)
+            .unwrap();
+        let commitment = commit_bytes(
+            &encode_polynomial(&poly),
+            &encode_polynomial(&witness),
+            &srs_bytes,
+        )
+        .unwrap();
+        assert_eq!(
+            decompress_g1_point(&mut commitment.clone()).unwrap(),
+            expected
+        );
+
+        assert!(matches!(
+            commit_bytes(&[0; 31], &encode_polynomial(&witness), &srs_bytes),
+            Err(CommitError::InvalidLength("Polynomial", 31))
+        ));
+        assert!(matches!(
+            commit_bytes(&[0xff; 32], &encode_polynomial(&witness), &srs_bytes),
+            Err(CommitError::NonCanonicalScalar("Polynomial", 0))
+        ));
+        assert!(matches!(
+            commit_bytes(
+                &encode_polynomial(&poly),
+                &encode_polynomial(&witness),
+                &srs_bytes[..8 * 48]
+            ),
+            Err(CommitError::Prover(ProverError::SRSLengthMismatch(8, 16)))
+        ));
+    }
+}
@@ -1,10 +1,44 @@
+pub mod domain;
+pub mod ffi;
 pub mod prover;
 pub mod serialize;
 pub mod srs;
+#[cfg(test)]
+pub mod test_fixtures;
 pub mod utils;
+pub mod verifier;
+
+use std::sync::OnceLock;
 
 use lambdaworks_math::elliptic_curve::{
-    short_weierstrass::curves::bls12_381::curve::BLS12381Curve, traits::IsEllipticCurve,
+    short_weierstrass::curves::bls12_381::{curve::BLS12381Curve, twist::BLS12381TwistCurve},
+    traits::IsEllipticCurve,
 };
 
+/// Projective BLS12-381 G1 point. SRS generation, the G1 FFT in `to_lagrange_basis` and
+/// the commitment MSMs all stay in these coordinates; points are only normalized to
+/// affine at the boundaries (serialization, printing, `commit_lagrange_affine`)
 pub type G1Point = <BLS12381Curve as IsEllipticCurve>::PointRepresentation;
+pub type G2Point = <BLS12381TwistCurve as IsEllipticCurve>::PointRepresentation;
+
+/// The BLS12-381 G1 generator every SRS is built on, computed once
+pub fn g1_generator() -> G1Point {
+    static GENERATOR: OnceLock<G1Point> = OnceLock::new();
+    GENERATOR
+        .get_or_init(<BLS12381Curve as IsEllipticCurve>::generator)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_g1_generator() {
+        assert_eq!(
+            g1_generator(),
+            <BLS12381Curve as IsEllipticCurve>::generator()
+        );
+        assert_eq!(g1_generator(), g1_generator());
+    }
+}
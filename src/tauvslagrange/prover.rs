@@ -1,25 +1,67 @@
 use std::{error::Error, fmt};
 
 use lambdaworks_math::{
+    cyclic_group::IsGroup,
     elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrElement,
-    fft::{errors::FFTError, polynomial::FFTPoly},
-    msm::naive::MSMError,
-    msm::pippenger::parallel_msm_with,
+    fft::{
+        cpu::{
+            bit_reversing::in_place_bit_reverse_permute, fft::in_place_nr_2radix_fft,
+            roots_of_unity,
+        },
+        errors::FFTError,
+        polynomial::FFTPoly,
+    },
+    field::traits::RootsConfig,
+    msm::naive::{msm, MSMError},
+    msm::pippenger::{msm_with, parallel_msm_with},
     polynomial::Polynomial,
+    unsigned_integer::element::U256,
 };
 use rayon::prelude::*;
 
-use crate::G1Point;
+use crate::{
+    domain::{Domain, DomainError},
+    srs::{assert_power_of_two, for_degree, quotient_srs, SrsError, TaggedSrs},
+    utils::{
+        all_distinct, chunked_challenge, fiat_shamir_challenge, is_reduced, mul_evaluations,
+        scalar_mul, to_decimal_string, Basis,
+    },
+    G1Point,
+};
 
 #[derive(Debug)]
 pub enum ProverError {
     InvalidFFTOperation(String),
+    SRSLengthMismatch(usize, usize),
+    DuplicateOpeningPoints,
+    NonCanonicalCoefficient(usize),
+    BasisMismatch(Basis, Basis),
+    InvalidMsmWindow(usize),
 }
 
 impl fmt::Display for ProverError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             ProverError::InvalidFFTOperation(ref err) => write!(f, "Invalid FFT Op: {}", err),
+            ProverError::SRSLengthMismatch(srs_len, domain_len) => write!(
+                f,
+                "SRS length {} does not match evaluation domain size {}",
+                srs_len, domain_len
+            ),
+            ProverError::DuplicateOpeningPoints => {
+                write!(f, "Batch opening points must be distinct")
+            }
+            ProverError::BasisMismatch(expected, found) => write!(
+                f,
+                "Expected an SRS in the {:?} basis, got one in the {:?} basis",
+                expected, found
+            ),
+            ProverError::NonCanonicalCoefficient(i) => {
+                write!(f, "Witness coefficient {} is not canonically reduced", i)
+            }
+            ProverError::InvalidMsmWindow(window) => {
+                write!(f, "MSM window {} is outside 1..64", window)
+            }
         }
     }
 }
@@ -32,54 +74,1017 @@ impl From<FFTError> for ProverError {
     }
 }
 
+impl From<DomainError> for ProverError {
+    fn from(err: DomainError) -> Self {
+        ProverError::InvalidFFTOperation(err.to_string())
+    }
+}
+
+impl From<SrsError> for ProverError {
+    fn from(err: SrsError) -> Self {
+        ProverError::InvalidFFTOperation(err.to_string())
+    }
+}
+
 impl From<MSMError> for ProverError {
     fn from(err: MSMError) -> Self {
         ProverError::InvalidFFTOperation(err.to_string())
     }
 }
 
+/// Number of scalars accumulated per MSM when committing from an iterator
+const ITER_CHUNK_SIZE: usize = 1 << 12;
+
+/// Compute the optimal window size for the multi-scalar multiplication
+pub(crate) fn msm_window_size(len: usize) -> usize {
+    const SCALE_FACTORS: (usize, usize) = (4, 5);
+    // We approximate the optimum window size with: f(n) = k * log2(n), where k is a scaling factor
+    let len_isqrt = len.checked_ilog2().unwrap_or(0);
+    // Pippenger divides by the window size, so short inputs still get a 1-bit window
+    ((len_isqrt as usize * SCALE_FACTORS.0) / SCALE_FACTORS.1).max(1)
+}
+
+/// Sum all points in parallel
+pub(crate) fn sum_points(points: &[G1Point]) -> G1Point {
+    points
+        .par_iter()
+        .cloned()
+        .reduce(G1Point::neutral_element, |a, b| a.operate_with(&b))
+}
+
+/// Multi-scalar multiplication used by the commit functions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MsmBackend {
+    /// One scalar multiplication per point, summed sequentially
+    Naive,
+    /// Parallel Pippenger with the given window size, or one scaled with log2 of
+    /// the input length when `None`
+    Pippenger { window: Option<usize> },
+    /// Single-threaded Pippenger, for callers that already parallelize around the MSM
+    SequentialPippenger { window: Option<usize> },
+}
+
+impl Default for MsmBackend {
+    fn default() -> Self {
+        MsmBackend::Pippenger { window: None }
+    }
+}
+
+impl MsmBackend {
+    /// `sum_i scalars[i] * points[i]` over the first `scalars.len()` points. An explicit
+    /// Pippenger window must be in `1..64`
+    pub fn msm(&self, scalars: &[U256], points: &[G1Point]) -> Result<G1Point, ProverError> {
+        if points.len() < scalars.len() {
+            return Err(ProverError::SRSLengthMismatch(points.len(), scalars.len()));
+        }
+        if let MsmBackend::Pippenger {
+            window: Some(window),
+        }
+        | MsmBackend::SequentialPippenger {
+            window: Some(window),
+        } = *self
+        {
+            if !(1..64).contains(&window) {
+                return Err(ProverError::InvalidMsmWindow(window));
+            }
+        }
+        let points = &points[..scalars.len()];
+
+        match *self {
+            MsmBackend::Naive => Ok(msm(scalars, points)?),
+            MsmBackend::Pippenger { window } => {
+                let window_size = window.unwrap_or_else(|| msm_window_size(scalars.len()));
+                Ok(parallel_msm_with(scalars, points, window_size))
+            }
+            MsmBackend::SequentialPippenger { window } => {
+                let window_size = window.unwrap_or_else(|| msm_window_size(scalars.len()));
+                Ok(msm_with(scalars, points, window_size))
+            }
+        }
+    }
+}
+
+/// Sequential bucket-method MSM with the window width [`msm_window_size`] picks,
+/// returning the commitment and the per-window sums `W_k`, and adding every group
+/// addition and doubling it performs to `additions`
+fn bucket_msm(
+    scalars: &[U256],
+    points: &[G1Point],
+    additions: &mut u64,
+) -> (G1Point, Vec<G1Point>) {
+    let window_size = msm_window_size(scalars.len());
+    let num_windows = 256usize.div_ceil(window_size);
+    let mask = (1u64 << window_size) - 1;
+
+    let mut add = |a: &G1Point, b: &G1Point| {
+        *additions += 1;
+        a.operate_with(b)
+    };
+
+    let windows = (0..num_windows)
+        .map(|k| {
+            let mut buckets = vec![G1Point::neutral_element(); mask as usize];
+            for (scalar, point) in scalars.iter().zip(points) {
+                let digit = (scalar >> (k * window_size)).limbs[3] & mask;
+                if digit != 0 {
+                    buckets[digit as usize - 1] = add(&buckets[digit as usize - 1], point);
+                }
+            }
+
+            // sum_d d * B_d via running sums from the top bucket down
+            let mut running = G1Point::neutral_element();
+            let mut window = G1Point::neutral_element();
+            for bucket in buckets.iter().rev() {
+                running = add(&running, bucket);
+                window = add(&window, &running);
+            }
+            window
+        })
+        .collect::<Vec<_>>();
+
+    // sum_k 2^(k * c) * W_k, shifting by c doublings per window
+    let commitment = windows
+        .iter()
+        .rev()
+        .fold(G1Point::neutral_element(), |acc, w| {
+            let shifted = (0..window_size).fold(acc, |acc, _| add(&acc, &acc));
+            add(&shifted, w)
+        });
+
+    (commitment, windows)
+}
+
+/// Where [`Prover::commit_batch`] spends its threads. Nesting a parallel MSM inside a
+/// parallel loop over the batch oversubscribes the pool, so only one level runs parallel
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchParallelism {
+    /// Parallel across witnesses, each MSM sequential. Pick this for many small
+    /// witnesses, roughly when the batch has at least as many entries as there are threads
+    Outer,
+    /// Sequential across witnesses, each MSM parallel. Pick this for a few large witnesses
+    #[default]
+    Inner,
+}
+
+/// Stage of [`Prover::commit_polynomial`], as reported by
+/// [`Prover::commit_polynomial_profiled`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Evaluating the witness over the domain and multiplying by the prover polynomial
+    Fft,
+    /// Interpolating the product back to coefficients, which the Lagrange path skips
+    Interpolation,
+    /// The multi-scalar multiplication against the SRS
+    Msm,
+}
+
+/// Open a polynomial at `point` using the powers of tau (KZG).
+/// Returns the evaluation `p(point)` and the commitment to the quotient
+/// `q(x) = (p(x) - p(point)) / (x - point)`
+pub fn open(
+    poly: &Polynomial<FrElement>,
+    point: &FrElement,
+    pwrs_tau: &[G1Point],
+) -> Result<(FrElement, G1Point), ProverError> {
+    let value = poly.evaluate(point);
+
+    // the remainder of the division by (x - point) is p(point), which ruffini drops
+    let mut quotient = poly.clone();
+    quotient.ruffini_division_inplace(point);
+
+    Ok((
+        value,
+        commit_coefficients(&quotient, quotient_srs(pwrs_tau))?,
+    ))
+}
+
+/// Open a polynomial at several distinct `points` with a single proof (KZG).
+/// Returns the evaluations and the commitment to `q(x) = (p(x) - I(x)) / Z(x)`, where
+/// `I` interpolates the evaluations and `Z = prod (x - z_i)` vanishes on the points.
+/// Repeated points would give `Z` a double root, so they are rejected
+pub fn open_batch(
+    poly: &Polynomial<FrElement>,
+    points: &[FrElement],
+    pwrs_tau: &[G1Point],
+) -> Result<(Vec<FrElement>, G1Point), ProverError> {
+    if !all_distinct(points) {
+        return Err(ProverError::DuplicateOpeningPoints);
+    }
+
+    let values = poly.evaluate_slice(points);
+    let interpolant = Polynomial::interpolate(points, &values)
+        .map_err(|err| ProverError::InvalidFFTOperation(format!("{:?}", err)))?;
+
+    // p - I vanishes on every point, so dividing by each (x - z_i) in turn is exact
+    let mut quotient = poly.clone() - interpolant;
+    for point in points {
+        quotient.ruffini_division_inplace(point);
+    }
+
+    Ok((
+        values,
+        commit_coefficients(&quotient, quotient_srs(pwrs_tau))?,
+    ))
+}
+
+/// Commit to the product of `poly` and `witness` with the powers of tau without any
+/// length bookkeeping: the prover domain is sized for the longer of the two (padded
+/// to a power of two, blowup 2) and only the SRS prefix it needs is used
+pub fn commit_auto(
+    poly: &Polynomial<FrElement>,
+    witness: &Polynomial<FrElement>,
+    pwrs_tau: &[G1Point],
+) -> Result<G1Point, ProverError> {
+    let coeff_len = poly.coeff_len().max(witness.coeff_len()).max(1);
+    let domain = Domain::for_coeff_len(coeff_len, 2)?;
+
+    Prover::new_with_domain(poly.clone(), &domain)?.commit_polynomial(witness, pwrs_tau)
+}
+
+/// Commit to the bivariate polynomial `sum_{i,j} coefficients[i][j] * x^i * y^j`
+/// against a row-major SRS from [`crate::srs::generate_bivariate_srs`] with rows of
+/// `m` points. Rows may be shorter than `m`; there may be at most `srs.len() / m` of them
+pub fn commit_bivariate(
+    coefficients: &[Vec<FrElement>],
+    bivariate_srs: &[G1Point],
+    m: usize,
+) -> Result<G1Point, ProverError> {
+    let rows = bivariate_srs.len().checked_div(m).unwrap_or(0);
+    if coefficients.len() > rows || coefficients.iter().any(|row| row.len() > m) {
+        let widest = coefficients.iter().map(Vec::len).max().unwrap_or(0);
+        return Err(ProverError::SRSLengthMismatch(
+            bivariate_srs.len(),
+            coefficients.len() * widest.max(m),
+        ));
+    }
+
+    let (scalars, points): (Vec<_>, Vec<_>) = coefficients
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(j, c)| (c.representative(), bivariate_srs[i * m + j].clone()))
+        })
+        .unzip();
+
+    MsmBackend::default().msm(&scalars, &points)
+}
+
+/// Commit to a polynomial in coefficient form using a prefix of the powers of tau
+fn commit_coefficients(
+    poly: &Polynomial<FrElement>,
+    pwrs_tau: &[G1Point],
+) -> Result<G1Point, ProverError> {
+    if poly.coeff_len() > pwrs_tau.len() {
+        return Err(ProverError::SRSLengthMismatch(
+            pwrs_tau.len(),
+            poly.coeff_len(),
+        ));
+    }
+
+    let coeff = poly
+        .coefficients()
+        .par_iter()
+        .map(|c| c.representative())
+        .collect::<Vec<_>>();
+    MsmBackend::default().msm(&coeff, pwrs_tau)
+}
+
+/// Cross-check a few random FFT evaluations against direct Horner evaluation at the
+/// corresponding domain points. Compiled out of release builds
+#[cfg(debug_assertions)]
+fn debug_assert_evaluations(
+    poly: &Polynomial<FrElement>,
+    evaluations: &[FrElement],
+    domain: &Domain,
+) {
+    use rand::Rng;
+
+    const SAMPLES: usize = 2;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..SAMPLES {
+        let i = rng.gen_range(0..evaluations.len());
+        assert_eq!(
+            poly.evaluate(&domain.element(i)),
+            evaluations[i],
+            "FFT evaluation {} does not match direct evaluation",
+            i
+        );
+    }
+}
+
 /// Very basic prover that uses the SRS to commit to a polynomial
 pub struct Prover {
     poly_eval: Vec<FrElement>,
+    domain: Domain,
+}
+
+/// A witness polynomial evaluated once over a domain, for committing the same witness
+/// against many provers on that domain without redoing its FFT
+pub struct Witness {
+    evaluations: Vec<FrElement>,
+    domain: Domain,
+}
+
+impl Witness {
+    pub fn new(witness: &Polynomial<FrElement>, domain: &Domain) -> Result<Self, ProverError> {
+        if witness.coeff_len() > domain.coeff_size() {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "Witness with {} coefficients does not fit a domain of {}",
+                witness.coeff_len(),
+                domain.coeff_size()
+            )));
+        }
+
+        Ok(Witness {
+            evaluations: domain.evaluate(witness)?,
+            domain: domain.clone(),
+        })
+    }
+
+    /// The witness evaluations over its domain
+    pub fn evaluations(&self) -> &[FrElement] {
+        &self.evaluations
+    }
 }
 
 impl Prover {
     /// Create a new prover instance
     pub fn new(poly: Polynomial<FrElement>) -> Result<Self, ProverError> {
-        let eval = poly.evaluate_fft(2, None)?;
-        Ok(Prover { poly_eval: eval })
+        Prover::new_with_blowup(poly, 2)
+    }
+
+    /// Create a new prover instance evaluating on a domain `blowup` times larger than the
+    /// polynomial. The Lagrange SRS passed to the commit functions must be sized for it
+    pub fn new_with_blowup(
+        poly: Polynomial<FrElement>,
+        blowup: usize,
+    ) -> Result<Self, ProverError> {
+        let domain = Domain::for_coeff_len(poly.coeff_len(), blowup)?;
+        Prover::new_with_domain(poly, &domain)
+    }
+
+    /// Create a prover straight from the polynomial's evaluations, skipping the FFT in
+    /// [`Prover::new`]. The evaluations must be over the same domain `Prover::new` uses:
+    /// the subgroup of size `evals.len()` (a power of two, at least 2) in natural order,
+    /// with blowup 2, i.e. a polynomial of degree below `evals.len() / 2`
+    pub fn from_lagrange_evals(evals: Vec<FrElement>) -> Result<Self, ProverError> {
+        if evals.len() < 2 || !evals.len().is_power_of_two() {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "{} evaluations do not fill a blowup 2 domain",
+                evals.len()
+            )));
+        }
+        let domain = Domain::new((evals.len() / 2).trailing_zeros(), 2)?;
+
+        Ok(Prover {
+            poly_eval: evals,
+            domain,
+        })
+    }
+
+    /// The domain the prover polynomial is evaluated on
+    pub fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    /// The evaluation points of the domain, `offset * w^i` including the blowup, in the
+    /// order of [`Prover::evaluations`], so `evaluations()[i] = p(domain_elements()[i])`
+    pub fn domain_elements(&self) -> Vec<FrElement> {
+        // the domain order was checked against the field's 2-adicity on construction
+        let roots = roots_of_unity::get_powers_of_primitive_root(
+            self.domain.order().into(),
+            self.domain.size(),
+            RootsConfig::Natural,
+        )
+        .unwrap();
+
+        match self.domain.offset() {
+            Some(offset) => roots.into_iter().map(|w| w * offset).collect(),
+            None => roots,
+        }
+    }
+
+    /// The prover polynomial's evaluations over its domain, in natural domain order
+    pub fn evaluations(&self) -> &[FrElement] {
+        &self.poly_eval
+    }
+
+    /// Create a new prover instance evaluating on the given domain. Committing on a coset
+    /// domain requires a Lagrange SRS for the same coset
+    pub fn new_with_domain(
+        poly: Polynomial<FrElement>,
+        domain: &Domain,
+    ) -> Result<Self, ProverError> {
+        if poly.coeff_len() > domain.coeff_size() {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "Polynomial with {} coefficients does not fit a domain of {}",
+                poly.coeff_len(),
+                domain.coeff_size()
+            )));
+        }
+
+        let eval = domain.evaluate(&poly)?;
+
+        #[cfg(debug_assertions)]
+        debug_assert_evaluations(&poly, &eval, domain);
+
+        Ok(Prover {
+            poly_eval: eval,
+            domain: domain.clone(),
+        })
     }
 
-    /// Commit to the polynomial using the Lagrange basis
+    /// Commit to the polynomial using the Lagrange basis.
+    /// The witness is in coefficient form, so its FFT over the domain (blowup included)
+    /// is needed even when its length matches the domain: the coefficients are not its
+    /// evaluations. Witnesses already in evaluation form skip the FFT through
+    /// [`Prover::commit_lagrange_repr`] or a precomputed [`Witness`]
     pub fn commit_lagrange(
         &self,
         witness: &Polynomial<FrElement>,
         lagrange_srs: &[G1Point],
     ) -> Result<G1Point, ProverError> {
-        let witness_eval = witness.evaluate_fft(2, None)?;
+        self.commit_lagrange_with_backend(witness, lagrange_srs, MsmBackend::default())
+    }
+
+    /// Commit to the product of the prover polynomial and every polynomial in `others`
+    /// using the Lagrange basis, multiplying all of them pointwise on the domain. The
+    /// product is only recovered correctly if its degree is below the domain size, so a
+    /// larger combined degree is an error; raise the blowup to fit more factors
+    pub fn commit_product(
+        &self,
+        others: &[&Polynomial<FrElement>],
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        assert_power_of_two(lagrange_srs)?;
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+
+        let size = self.domain.size();
+        let degree = self.domain.interpolate(&self.poly_eval)?.degree()
+            + others.iter().map(|p| p.degree()).sum::<usize>();
+        if degree >= size {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "Product of degree {} does not fit a domain of {}",
+                degree, size
+            )));
+        }
+
+        let mut product = self.poly_eval.clone();
+        for other in others {
+            let evaluations = match self.domain.offset() {
+                Some(offset) => other.evaluate_offset_fft(1, Some(size), offset)?,
+                None => other.evaluate_fft(1, Some(size))?,
+            };
+            product = mul_evaluations(&product, &evaluations)?;
+        }
+
+        let scalars = product
+            .par_iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+        MsmBackend::default().msm(&scalars, lagrange_srs)
+    }
+
+    /// [`Prover::commit_lagrange`] after checking that every witness coefficient is stored
+    /// canonically reduced. Elements built with `FieldElement::from_raw` from unreduced
+    /// limbs skip the reduction and can commit to something other than intended
+    pub fn commit_lagrange_checked(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        if let Some(i) = witness
+            .coefficients()
+            .iter()
+            .position(|c| !is_reduced(c.value()))
+        {
+            return Err(ProverError::NonCanonicalCoefficient(i));
+        }
+
+        self.commit_lagrange(witness, lagrange_srs)
+    }
+
+    /// [`Prover::commit_lagrange`] against an SRS that must be tagged [`Basis::Lagrange`]
+    pub fn commit_lagrange_tagged(
+        &self,
+        witness: &Polynomial<FrElement>,
+        srs: &TaggedSrs,
+    ) -> Result<G1Point, ProverError> {
+        self.commit_lagrange(witness, srs.points_in(Basis::Lagrange)?)
+    }
+
+    /// [`Prover::commit_lagrange`] with an explicit MSM backend
+    pub fn commit_lagrange_with_backend(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+        backend: MsmBackend,
+    ) -> Result<G1Point, ProverError> {
+        let evaluations = self
+            .lagrange_product(witness, lagrange_srs)?
+            .par_iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+
+        backend.msm(&evaluations, lagrange_srs)
+    }
+
+    /// [`Prover::commit_lagrange`] given the precomputed [`crate::utils::srs_sum`] of the Lagrange SRS,
+    /// so a constant product, e.g. from a constant witness, commits to
+    /// `scalar * srs_sum` without an MSM. Costs one scan of the product evaluations
+    pub fn commit_lagrange_with_sum(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+        srs_sum: &G1Point,
+    ) -> Result<G1Point, ProverError> {
+        let evaluations = self.lagrange_product(witness, lagrange_srs)?;
+
+        if let Some(first) = evaluations.first() {
+            if evaluations.par_iter().all(|e| e == first) {
+                return Ok(scalar_mul(srs_sum, first));
+            }
+        }
+
+        let evaluations = evaluations
+            .par_iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+
+        MsmBackend::default().msm(&evaluations, lagrange_srs)
+    }
+
+    /// [`Prover::commit_lagrange`] for every witness in `witnesses`, in order, with the
+    /// parallelism placed per `strategy`
+    pub fn commit_batch(
+        &self,
+        witnesses: &[Polynomial<FrElement>],
+        lagrange_srs: &[G1Point],
+        strategy: BatchParallelism,
+    ) -> Result<Vec<G1Point>, ProverError> {
+        match strategy {
+            BatchParallelism::Outer => witnesses
+                .par_iter()
+                .map(|witness| {
+                    self.commit_lagrange_with_backend(
+                        witness,
+                        lagrange_srs,
+                        MsmBackend::SequentialPippenger { window: None },
+                    )
+                })
+                .collect(),
+            BatchParallelism::Inner => witnesses
+                .iter()
+                .map(|witness| self.commit_lagrange(witness, lagrange_srs))
+                .collect(),
+        }
+    }
+
+    /// [`Prover::commit_lagrange`] for every witness in `witnesses`, in order, computing the
+    /// FFT twiddles (and coset offset powers) once for the batch and evaluating each
+    /// witness in one reused scratch buffer, instead of reallocating both per witness
+    pub fn commit_batch_lagrange_shared(
+        &self,
+        witnesses: &[Polynomial<FrElement>],
+        lagrange_srs: &[G1Point],
+    ) -> Result<Vec<G1Point>, ProverError> {
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+
+        let size = self.domain.size();
+        let twiddles = self.domain.twiddles(RootsConfig::BitReverse)?;
+        let offset_powers = self.domain.offset().map(|offset| {
+            std::iter::successors(Some(FrElement::one()), |p| Some(p * offset))
+                .take(size)
+                .collect::<Vec<_>>()
+        });
+
+        let mut scratch = vec![FrElement::zero(); size];
+        witnesses
+            .iter()
+            .map(|witness| {
+                self.check_witness_fits(witness, lagrange_srs.len())?;
+
+                // coefficients, scaled by offset^i on a coset, zero-padded to the domain
+                scratch.fill(FrElement::zero());
+                for (i, c) in witness.coefficients().iter().enumerate() {
+                    scratch[i] = match &offset_powers {
+                        Some(powers) => c * &powers[i],
+                        None => c.clone(),
+                    };
+                }
+                in_place_nr_2radix_fft(&mut scratch, &twiddles);
+                in_place_bit_reverse_permute(&mut scratch);
+
+                let scalars = scratch
+                    .par_iter()
+                    .zip(&self.poly_eval)
+                    .map(|(w, p)| (w * p).representative())
+                    .collect::<Vec<_>>();
+                MsmBackend::default().msm(&scalars, lagrange_srs)
+            })
+            .collect()
+    }
+
+    /// Commit like [`Prover::commit_lagrange`] with a plain bucket-method MSM, also
+    /// returning the per-window partial sums `W_k = sum_i digit_k(s_i) * L_i` for
+    /// diffing against a reference implementation. The commitment is
+    /// `sum_k 2^(k * c) * W_k` for the window width `c` the MSM would pick.
+    /// A development aid, much slower than the parallel MSM
+    pub fn commit_lagrange_debug(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<(G1Point, Vec<G1Point>), ProverError> {
+        let scalars = self
+            .lagrange_product(witness, lagrange_srs)?
+            .iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+
+        let mut additions = 0;
+        Ok(bucket_msm(&scalars, lagrange_srs, &mut additions))
+    }
+
+    /// Commit like [`Prover::commit_lagrange`], also returning the number of group
+    /// additions (doublings included) the MSM performs. The count comes from the plain
+    /// bucket method of [`Prover::commit_lagrange_debug`], not the parallel Pippenger
+    /// MSM, which lambdaworks does not instrument
+    #[cfg(feature = "op-count")]
+    pub fn commit_lagrange_counted(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<(G1Point, u64), ProverError> {
+        let scalars = self
+            .lagrange_product(witness, lagrange_srs)?
+            .iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+
+        let mut additions = 0;
+        let (commitment, _) = bucket_msm(&scalars, lagrange_srs, &mut additions);
+        Ok((commitment, additions))
+    }
+
+    /// A witness of higher degree than the domain holds is evaluated on a larger domain
+    /// (e.g. degree n against a prover built for degree n-1), so its evaluations would
+    /// outgrow the SRS. Reject it up front, naming both lengths
+    fn check_witness_fits(
+        &self,
+        witness: &Polynomial<FrElement>,
+        srs_len: usize,
+    ) -> Result<(), ProverError> {
+        let eval_len = for_degree(witness.degree(), self.domain.blowup()).max(self.domain.size());
+        if eval_len > srs_len {
+            return Err(ProverError::SRSLengthMismatch(srs_len, eval_len));
+        }
+        Ok(())
+    }
+
+    /// Evaluations of the witness times the polynomial over the domain, after checking
+    /// that the Lagrange SRS matches the domain
+    fn lagrange_product(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<Vec<FrElement>, ProverError> {
+        assert_power_of_two(lagrange_srs)?;
+        // the Lagrange basis is tied to its domain, so the SRS must cover it exactly
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+        self.check_witness_fits(witness, lagrange_srs.len())?;
+
+        let witness_eval = self.domain.evaluate(witness)?;
+
+        // multiply polynomials in evaluated form
+        mul_evaluations(&witness_eval, &self.poly_eval)
+    }
+
+    /// Commit to the polynomial using the Lagrange basis, with the witness already given
+    /// as the representatives of its evaluations over the domain. This skips the witness
+    /// FFT; each representative still enters the field once for the product with the
+    /// polynomial evaluations, since the MSM scalars are those products
+    pub fn commit_lagrange_repr(
+        &self,
+        reps: &[U256],
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+        if reps.len() != self.poly_eval.len() {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "Evaluation lengths do not match: {} != {}",
+                reps.len(),
+                self.poly_eval.len()
+            )));
+        }
+
+        let evaluations = reps
+            .par_iter()
+            .zip(self.poly_eval.par_iter())
+            .map(|(r, e)| (FrElement::new(*r) * e).representative())
+            .collect::<Vec<_>>();
+
+        MsmBackend::default().msm(&evaluations, lagrange_srs)
+    }
+
+    /// [`Prover::commit_lagrange`] with the witness FFT precomputed in a [`Witness`],
+    /// which must be evaluated on this prover's domain
+    pub fn commit_lagrange_witness(
+        &self,
+        witness: &Witness,
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        if witness.domain != self.domain {
+            return Err(ProverError::InvalidFFTOperation(
+                "Witness was evaluated on a different domain".to_string(),
+            ));
+        }
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+
+        let evaluations = mul_evaluations(&witness.evaluations, &self.poly_eval)?
+            .par_iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+        MsmBackend::default().msm(&evaluations, lagrange_srs)
+    }
+
+    /// Commit to the polynomial using the Lagrange basis, returning the commitment in
+    /// affine form (z = 1). The point at infinity is returned as is
+    pub fn commit_lagrange_affine(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        let commitment = self.commit_lagrange(witness, lagrange_srs)?;
+        if commitment.is_neutral_element() {
+            return Ok(commitment);
+        }
+
+        Ok(commitment.to_affine())
+    }
+
+    /// [`Prover::commit_lagrange`] as affine `x`, `y` in base 10 rather than the hex
+    /// `main.rs` prints. The point at infinity has no affine form and gives `("0", "0")`,
+    /// which is not on the curve
+    pub fn commit_lagrange_decimal(
+        &self,
+        witness: &Polynomial<FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<(String, String), ProverError> {
+        let commitment = self.commit_lagrange(witness, lagrange_srs)?;
+        if commitment.is_neutral_element() {
+            return Ok(("0".to_string(), "0".to_string()));
+        }
+
+        let affine = commitment.to_affine();
+        Ok((
+            to_decimal_string(&affine.x().representative()),
+            to_decimal_string(&affine.y().representative()),
+        ))
+    }
+
+    /// Commit to the polynomial using the Lagrange basis, consuming the witness
+    /// evaluations lazily. Scalars are accumulated in fixed-size chunks, so only
+    /// one chunk of representatives is materialized at a time
+    pub fn commit_lagrange_iter(
+        &self,
+        scalars: impl Iterator<Item = FrElement>,
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        if lagrange_srs.len() != self.poly_eval.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                lagrange_srs.len(),
+                self.poly_eval.len(),
+            ));
+        }
+
+        let mut scalars = scalars.fuse();
+        let mut commitment = G1Point::neutral_element();
+        let mut consumed = 0;
+
+        for (poly_chunk, srs_chunk) in self
+            .poly_eval
+            .chunks(ITER_CHUNK_SIZE)
+            .zip(lagrange_srs.chunks(ITER_CHUNK_SIZE))
+        {
+            // multiply polynomials in evaluated form
+            let evaluations = scalars
+                .by_ref()
+                .take(poly_chunk.len())
+                .zip(poly_chunk)
+                .map(|(w, e)| (w * e).representative())
+                .collect::<Vec<_>>();
+            consumed += evaluations.len();
+
+            if evaluations.len() != poly_chunk.len() {
+                break;
+            }
+
+            let partial = MsmBackend::default().msm(&evaluations, srs_chunk)?;
+            commitment = commitment.operate_with(&partial);
+        }
 
         // verify that the witness is of the same length as the polynomial
-        if witness_eval.len() != self.poly_eval.len() {
+        consumed += scalars.count();
+        if consumed != self.poly_eval.len() {
             return Err(ProverError::InvalidFFTOperation(
                 "Witness length does not match polynomial length".to_string(),
             ));
         }
 
-        // multiply polynomials in evaluated form
-        let evaluations = witness_eval
+        Ok(commitment)
+    }
+
+    /// Commit to the polynomial using the Lagrange basis, with the i-th witness
+    /// evaluation computed on demand by `f(i)`. Goes through
+    /// [`Prover::commit_lagrange_iter`], so only one chunk of scalars is alive at a time
+    pub fn commit_lagrange_fn(
+        &self,
+        f: impl Fn(usize) -> FrElement,
+        lagrange_srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        self.commit_lagrange_iter((0..self.poly_eval.len()).map(f), lagrange_srs)
+    }
+
+    /// Commit to a vector longer than the Lagrange SRS by splitting it into chunks of
+    /// `lagrange_srs.len()` evaluations (the last one zero-padded). Each chunk is committed
+    /// as is, an MSM of its evaluations against the SRS, and the chunk commitments are
+    /// combined with powers of a challenge `r`: `C = sum_k r^k * C_k`. The challenge is
+    /// [`chunked_challenge`] of the chunk commitments, so a verifier holding them derives
+    /// the same `r`. Returns the aggregate and `r`.
+    /// A single chunk gives the plain Lagrange commitment to the data
+    pub fn commit_chunked(
+        data_evals: &[FrElement],
+        lagrange_srs: &[G1Point],
+    ) -> Result<(G1Point, FrElement), ProverError> {
+        if lagrange_srs.is_empty() {
+            return Err(ProverError::SRSLengthMismatch(0, 1));
+        }
+
+        let chunk_commitments = data_evals
+            .chunks(lagrange_srs.len())
+            .map(|chunk| {
+                let scalars = chunk
+                    .par_iter()
+                    .map(|e| e.representative())
+                    .collect::<Vec<_>>();
+                MsmBackend::default().msm(&scalars, lagrange_srs)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let challenge = chunked_challenge(&chunk_commitments);
+        let mut power = FrElement::one();
+        let mut commitment = G1Point::neutral_element();
+        for chunk_commitment in &chunk_commitments {
+            commitment = commitment.operate_with(&scalar_mul(chunk_commitment, &power));
+            power = power * &challenge;
+        }
+
+        Ok((commitment, challenge))
+    }
+
+    /// Commit to a multilinear polynomial given by its evaluations over the boolean
+    /// hypercube {0, 1}^k, in lexicographic order. The evaluations are the scalars of a
+    /// direct MSM against the first `2^k` SRS points: the Lagrange commitment without the
+    /// FFT, since there is no univariate domain to move between
+    pub fn commit_multilinear(
+        evals: &[FrElement],
+        srs: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        if !evals.len().is_power_of_two() {
+            return Err(ProverError::InvalidFFTOperation(format!(
+                "{} evaluations do not cover a boolean hypercube",
+                evals.len()
+            )));
+        }
+        if srs.len() < evals.len() {
+            return Err(ProverError::SRSLengthMismatch(srs.len(), evals.len()));
+        }
+
+        let scalars = evals
             .par_iter()
-            .zip(&self.poly_eval)
-            .map(|(w, e)| (w * e).representative())
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+        MsmBackend::default().msm(&scalars, srs)
+    }
+
+    /// Commit to the product with the witness using the powers of tau and open it at a
+    /// Fiat-Shamir challenge derived from `transcript_seed` and the commitment
+    /// (see [`fiat_shamir_challenge`]), so the proof needs no interaction.
+    /// Returns the commitment, the evaluation at the challenge and the opening proof;
+    /// a verifier rederives the challenge from the seed and the commitment
+    pub fn commit_and_open(
+        &self,
+        witness: &Polynomial<FrElement>,
+        pwrs_tau: &[G1Point],
+        transcript_seed: &[u8],
+    ) -> Result<(G1Point, FrElement, G1Point), ProverError> {
+        let commitment = self.commit_polynomial(witness, pwrs_tau)?;
+        let challenge = fiat_shamir_challenge(transcript_seed, &commitment, self.domain.order());
+
+        let product = self.domain.interpolate(&mul_evaluations(
+            &self.domain.evaluate(witness)?,
+            &self.poly_eval,
+        )?)?;
+        let (value, proof) = open(&product, &challenge, pwrs_tau)?;
+
+        Ok((commitment, value, proof))
+    }
+
+    /// Commit to the derivative `p'(x)` of the prover polynomial using the powers of tau.
+    /// The coefficients are recovered from the domain evaluations; the constant term
+    /// drops out, so one fewer power of tau is used than for `p` itself
+    pub fn commit_derivative(&self, pwrs_tau: &[G1Point]) -> Result<G1Point, ProverError> {
+        let polynomial = self.domain.interpolate(&self.poly_eval)?;
+        let derivative = polynomial
+            .coefficients()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| FrElement::from(i as u64) * c)
             .collect::<Vec<_>>();
 
-        // Compute the optimal window size for the multi-scalar multiplication
-        const SCALE_FACTORS: (usize, usize) = (4, 5);
-        // We approximate the optimum window size with: f(n) = k * log2(n), where k is a scaling factor
-        let len_isqrt = evaluations.len().checked_ilog2().unwrap_or(0);
-        let window_size = (len_isqrt as usize * SCALE_FACTORS.0) / SCALE_FACTORS.1;
+        commit_coefficients(&Polynomial::new(&derivative), pwrs_tau)
+    }
+
+    /// Commit to the reciprocal polynomial `x^n * p(1/x)` with `n = deg(p)`, i.e. `p` with
+    /// its coefficients reversed, using the powers of tau. The coefficients are recovered
+    /// from the domain evaluations, where trailing zeros are already trimmed, so `n` is the
+    /// true degree. A zero constant term becomes a zero leading coefficient and is trimmed
+    /// too: the reciprocal then has degree below `n`, and reciprocating twice does not
+    /// give `p` back. The zero polynomial commits to the neutral element
+    pub fn commit_reciprocal(&self, pwrs_tau: &[G1Point]) -> Result<G1Point, ProverError> {
+        let polynomial = self.domain.interpolate(&self.poly_eval)?;
+        let mut reversed = polynomial.coefficients().to_vec();
+        reversed.reverse();
+
+        commit_coefficients(&Polynomial::new(&reversed), pwrs_tau)
+    }
+
+    /// Commit to the constant polynomial `p(x) = 1` using the powers of tau, which is just
+    /// `pwrs_tau[0] = G`. In the Lagrange basis the same commitment is the sum of all
+    /// points, so comparing the two checks an SRS pair is in the expected basis and order
+    pub fn commit_one(pwrs_tau: &[G1Point]) -> Result<G1Point, ProverError> {
+        pwrs_tau
+            .first()
+            .cloned()
+            .ok_or(ProverError::SRSLengthMismatch(0, 1))
+    }
+
+    /// Commit to the monic polynomial `prod_i (x - roots[i])` using the powers of tau,
+    /// e.g. the vanishing polynomial of a point set. Needs `roots.len() + 1` powers
+    pub fn commit_from_roots(
+        roots: &[FrElement],
+        pwrs_tau: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        let polynomial = roots
+            .iter()
+            .fold(Polynomial::new(&[FrElement::one()]), |acc, root| {
+                acc.mul_with_ref(&Polynomial::new(&[-root, FrElement::one()]))
+            });
+
+        commit_coefficients(&polynomial, pwrs_tau)
+    }
 
-        // Compute the multi-scalar multiplication in parallel
-        Ok(parallel_msm_with(&evaluations, lagrange_srs, window_size))
+    /// Commit to the coefficient vector of `a` followed by that of `b`, i.e. the
+    /// polynomial `a(x) + x^k * b(x)` with `k = a.coeff_len()`. Trailing zeros of `a` are
+    /// already trimmed by `Polynomial::new`, so `k` is `deg(a) + 1`. Needs
+    /// `a.coeff_len() + b.coeff_len()` powers of tau
+    pub fn commit_concat(
+        a: &Polynomial<FrElement>,
+        b: &Polynomial<FrElement>,
+        pwrs_tau: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        let concatenated = [a.coefficients(), b.coefficients()].concat();
+
+        commit_coefficients(&Polynomial::new(&concatenated), pwrs_tau)
     }
 
     /// Commit to the polynomial using the powers of tau
@@ -88,36 +1093,1054 @@ impl Prover {
         witness: &Polynomial<FrElement>,
         pwrs_tau: &[G1Point],
     ) -> Result<G1Point, ProverError> {
-        let witness_eval = witness.evaluate_fft(2, None)?;
+        self.commit_polynomial_with_backend(witness, pwrs_tau, MsmBackend::default())
+    }
+
+    /// [`Prover::commit_polynomial`] against an SRS that must be tagged [`Basis::Monomial`]
+    pub fn commit_polynomial_tagged(
+        &self,
+        witness: &Polynomial<FrElement>,
+        srs: &TaggedSrs,
+    ) -> Result<G1Point, ProverError> {
+        self.commit_polynomial(witness, srs.points_in(Basis::Monomial)?)
+    }
 
-        // verify that the witness is of the same length as the polynomial
-        if witness_eval.len() != self.poly_eval.len() {
-            return Err(ProverError::InvalidFFTOperation(
-                "Witness length does not match polynomial length".to_string(),
-            ));
+    /// [`Prover::commit_polynomial`] with an explicit MSM backend
+    pub fn commit_polynomial_with_backend(
+        &self,
+        witness: &Polynomial<FrElement>,
+        pwrs_tau: &[G1Point],
+        backend: MsmBackend,
+    ) -> Result<G1Point, ProverError> {
+        // the product can have as many coefficients as the domain has points
+        let required = for_degree(self.domain.coeff_size() - 1, self.domain.blowup());
+        if pwrs_tau.len() < required {
+            return Err(ProverError::SRSLengthMismatch(pwrs_tau.len(), required));
         }
+        self.check_witness_fits(witness, pwrs_tau.len())?;
+
+        let witness_eval = self.domain.evaluate(witness)?;
 
         // multiply polynomials in evaluated form
-        let evaluations = witness_eval
+        let evaluations = mul_evaluations(&witness_eval, &self.poly_eval)?;
+
+        Prover::interpolate_and_commit(&evaluations, self.domain.offset(), pwrs_tau, backend)
+    }
+
+    /// [`Prover::commit_polynomial`], also reporting which [`Phase`] took the longest
+    pub fn commit_polynomial_profiled(
+        &self,
+        witness: &Polynomial<FrElement>,
+        pwrs_tau: &[G1Point],
+    ) -> Result<(G1Point, Phase), ProverError> {
+        let required = for_degree(self.domain.coeff_size() - 1, self.domain.blowup());
+        if pwrs_tau.len() < required {
+            return Err(ProverError::SRSLengthMismatch(pwrs_tau.len(), required));
+        }
+        self.check_witness_fits(witness, pwrs_tau.len())?;
+
+        let start = std::time::Instant::now();
+        let witness_eval = self.domain.evaluate(witness)?;
+        let evaluations = mul_evaluations(&witness_eval, &self.poly_eval)?;
+        let fft = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let coeff = self
+            .domain
+            .interpolate(&evaluations)?
+            .coefficients()
             .par_iter()
-            .zip(&self.poly_eval)
-            .map(|(w, e)| (w * e))
+            .map(|c| c.representative())
             .collect::<Vec<_>>();
+        let interpolation = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let commitment = MsmBackend::default().msm(&coeff, pwrs_tau)?;
+        let msm = start.elapsed();
+
+        let dominant = [
+            (fft, Phase::Fft),
+            (interpolation, Phase::Interpolation),
+            (msm, Phase::Msm),
+        ]
+        .into_iter()
+        .max_by_key(|(elapsed, _)| *elapsed)
+        .map(|(_, phase)| phase)
+        .unwrap_or(Phase::Msm);
+
+        Ok((commitment, dominant))
+    }
+
+    /// Commit to the polynomial given by its evaluations over the power-of-two subgroup,
+    /// or over the coset `offset * <w>` when an offset is given, using powers of tau.
+    /// Interpolating coset evaluations without their offset yields the wrong coefficients
+    pub fn commit_evaluations(
+        evaluations: &[FrElement],
+        offset: Option<&FrElement>,
+        pwrs_tau: &[G1Point],
+    ) -> Result<G1Point, ProverError> {
+        Prover::interpolate_and_commit(evaluations, offset, pwrs_tau, MsmBackend::default())
+    }
+
+    fn interpolate_and_commit(
+        evaluations: &[FrElement],
+        offset: Option<&FrElement>,
+        pwrs_tau: &[G1Point],
+        backend: MsmBackend,
+    ) -> Result<G1Point, ProverError> {
+        if pwrs_tau.len() < evaluations.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                pwrs_tau.len(),
+                evaluations.len(),
+            ));
+        }
 
-        let polynomial = Polynomial::interpolate_fft(&evaluations)?;
+        let polynomial = match offset {
+            Some(offset) => Polynomial::interpolate_offset_fft(evaluations, offset)?,
+            None => Polynomial::interpolate_fft(evaluations)?,
+        };
         let coeff = polynomial
             .coefficients()
             .into_par_iter()
             .map(|c| c.representative())
             .collect::<Vec<_>>();
 
-        // Compute the optimal window size for the multi-scalar multiplication
-        const SCALE_FACTORS: (usize, usize) = (4, 5);
-        // We approximate the optimum window size with: f(n) = k * log2(n), where k is a scaling factor
-        let len_isqrt = evaluations.len().checked_ilog2().unwrap_or(0);
-        let window_size = (len_isqrt as usize * SCALE_FACTORS.0) / SCALE_FACTORS.1;
+        backend.msm(&coeff, pwrs_tau)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        srs::generate_srs,
+        test_fixtures::small_srs,
+        utils::{
+            random_field_elements, random_fr, random_poly, srs_sum, to_lagrange_basis,
+            to_lagrange_basis_with_domain,
+        },
+    };
+
+    use lambdaworks_math::elliptic_curve::{
+        short_weierstrass::curves::bls12_381::curve::{BLS12381Curve, BLS12381FieldElement},
+        traits::IsEllipticCurve,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_commit_lagrange_srs_length_mismatch() {
+        // degree 3 polynomial evaluated with blowup 2 gives a domain of size 8
+        let prover = Prover::new(random_poly(3)).unwrap();
+        let witness = random_poly(3);
+
+        let lagrange_srs = to_lagrange_basis(&generate_srs(4, FrElement::from(42))).unwrap();
+        let result = prover.commit_lagrange(&witness, &lagrange_srs);
+
+        assert!(matches!(result, Err(ProverError::SRSLengthMismatch(4, 8))));
+    }
+
+    #[test]
+    fn test_commit_witness_overflowing_srs() {
+        // main.rs at a small size: degree n-1 polynomials against a 2n SRS
+        let n = 8;
+        let srs = generate_srs(2 * n, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(n - 1)).unwrap();
+
+        let witness = random_poly(n - 1);
+        assert!(prover.commit_lagrange(&witness, &lagrange_srs).is_ok());
+        assert!(prover.commit_polynomial(&witness, &srs).is_ok());
+
+        // one degree more doubles the evaluation domain past the SRS
+        let witness = random_poly(n);
+        assert!(matches!(
+            prover.commit_lagrange(&witness, &lagrange_srs),
+            Err(ProverError::SRSLengthMismatch(16, 32))
+        ));
+        assert!(matches!(
+            prover.commit_polynomial(&witness, &srs),
+            Err(ProverError::SRSLengthMismatch(16, 32))
+        ));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "does not match direct evaluation")]
+    fn test_debug_assert_evaluations_corrupted() {
+        let poly = random_poly(7);
+        let corrupted = poly
+            .evaluate_fft(2, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e + FrElement::one())
+            .collect::<Vec<_>>();
+
+        debug_assert_evaluations(&poly, &corrupted, &Domain::new(3, 2).unwrap());
+    }
+
+    #[test]
+    fn test_commit_with_blowup() {
+        // degree 3 polynomial on a blowup 4 domain: 16 evaluations
+        let srs = generate_srs(16, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+
+        let prover = Prover::new_with_blowup(random_poly(3), 4).unwrap();
+        let witness = random_poly(3);
+
+        let commitment1 = prover.commit_polynomial(&witness, &srs).unwrap();
+        let commitment2 = prover.commit_lagrange(&witness, &lagrange_srs).unwrap();
+        assert_eq!(commitment1, commitment2);
+
+        // a Lagrange SRS sized for blowup 2 is rejected
+        let lagrange_srs = to_lagrange_basis(&generate_srs(8, FrElement::from(42))).unwrap();
+        assert!(matches!(
+            prover.commit_lagrange(&witness, &lagrange_srs),
+            Err(ProverError::SRSLengthMismatch(8, 16))
+        ));
+    }
+
+    #[test]
+    fn test_commit_lagrange_decimal() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let (x, y) = prover
+            .commit_lagrange_decimal(&witness, &lagrange_srs)
+            .unwrap();
+        let parse = |digits: &str| {
+            digits.bytes().fold(BLS12381FieldElement::zero(), |acc, d| {
+                acc * BLS12381FieldElement::from(10) + BLS12381FieldElement::from((d - b'0') as u64)
+            })
+        };
+        let point = BLS12381Curve::create_point_from_affine(parse(&x), parse(&y)).unwrap();
+
+        assert_eq!(
+            point,
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_commit_lagrange_checked() {
+        use lambdaworks_math::{
+            elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrConfig,
+            field::fields::montgomery_backed_prime_fields::IsModulus,
+        };
+
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        assert_eq!(
+            prover
+                .commit_lagrange_checked(&witness, &lagrange_srs)
+                .unwrap(),
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+
+        let mut coefficients = witness.coefficients().to_vec();
+        coefficients[2] = FrElement::from_raw(&FrConfig::MODULUS);
+        let result = prover.commit_lagrange_checked(&Polynomial::new(&coefficients), &lagrange_srs);
+        assert!(matches!(
+            result,
+            Err(ProverError::NonCanonicalCoefficient(2))
+        ));
+    }
+
+    #[test]
+    fn test_commit_product() {
+        let srs = generate_srs(8, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let (p, a, b) = (random_poly(3), random_poly(1), random_poly(2));
+
+        // degree 3 + 1 + 2 = 6 fits the 8-point domain
+        let explicit = p.mul_with_ref(&a).mul_with_ref(&b);
+        let prover = Prover::new(p).unwrap();
+        assert_eq!(
+            prover.commit_product(&[&a, &b], &lagrange_srs).unwrap(),
+            commit_coefficients(&explicit, &srs).unwrap()
+        );
+
+        // degree 3 + 3 + 2 = 8 wraps around the domain
+        let c = random_poly(3);
+        assert!(prover.commit_product(&[&c, &b], &lagrange_srs).is_err());
+    }
+
+    #[test]
+    fn test_commit_lagrange_witness() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let witness = random_poly(7);
+
+        let first = Prover::new(random_poly(7)).unwrap();
+        let precomputed = Witness::new(&witness, first.domain()).unwrap();
+        for prover in [first, Prover::new(random_poly(7)).unwrap()] {
+            assert_eq!(
+                prover
+                    .commit_lagrange_witness(&precomputed, &lagrange_srs)
+                    .unwrap(),
+                prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+            );
+        }
+
+        let other_domain = Domain::new(3, 4).unwrap();
+        let mismatched = Witness::new(&witness, &other_domain).unwrap();
+        assert!(Prover::new(random_poly(7))
+            .unwrap()
+            .commit_lagrange_witness(&mismatched, &lagrange_srs)
+            .is_err());
+        assert!(Witness::new(&random_poly(8), &other_domain).is_err());
+    }
+
+    #[test]
+    fn test_commit_tagged() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let monomial = TaggedSrs::new(Basis::Monomial, srs.clone());
+        let lagrange = TaggedSrs::new(Basis::Lagrange, to_lagrange_basis(&srs).unwrap());
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        assert_eq!(
+            prover.commit_lagrange_tagged(&witness, &lagrange).unwrap(),
+            prover
+                .commit_polynomial_tagged(&witness, &monomial)
+                .unwrap()
+        );
+
+        assert!(matches!(
+            prover.commit_lagrange_tagged(&witness, &monomial),
+            Err(ProverError::BasisMismatch(Basis::Lagrange, Basis::Monomial))
+        ));
+        assert!(matches!(
+            prover.commit_polynomial_tagged(&witness, &lagrange),
+            Err(ProverError::BasisMismatch(Basis::Monomial, Basis::Lagrange))
+        ));
+    }
+
+    #[cfg(feature = "op-count")]
+    #[test]
+    fn test_commit_lagrange_counted() {
+        let counted = |n: usize| {
+            let lagrange_srs =
+                to_lagrange_basis(&generate_srs(2 * n, FrElement::from(42))).unwrap();
+            let prover = Prover::new(random_poly(n - 1)).unwrap();
+            let witness = random_poly(n - 1);
+
+            let (commitment, additions) = prover
+                .commit_lagrange_counted(&witness, &lagrange_srs)
+                .unwrap();
+            assert_eq!(
+                commitment,
+                prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+            );
+            additions
+        };
+
+        let (small, large) = (counted(8), counted(32));
+        assert!(small > 0);
+        assert!(large > small);
+    }
+
+    #[test]
+    fn test_commit_lagrange_affine() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let projective = prover.commit_lagrange(&witness, &lagrange_srs).unwrap();
+        let affine = prover
+            .commit_lagrange_affine(&witness, &lagrange_srs)
+            .unwrap();
+
+        assert_eq!(affine.x(), projective.to_affine().x());
+        assert_eq!(affine.y(), projective.to_affine().y());
+        assert_eq!(*affine.z(), BLS12381FieldElement::one());
+    }
+
+    #[test]
+    fn test_commit_lagrange_constant_witness() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(8, FrElement::from(42))).unwrap();
+
+        // constant polynomials on a domain of 8: every product evaluation is 15
+        let prover = Prover::new_with_blowup(Polynomial::new(&[FrElement::from(3)]), 8).unwrap();
+        let witness = Polynomial::new(&[FrElement::from(5)]);
+
+        let sum = srs_sum(&lagrange_srs);
+        let commitment = prover
+            .commit_lagrange_with_sum(&witness, &lagrange_srs, &sum)
+            .unwrap();
+
+        let scalars = vec![FrElement::from(15).representative(); lagrange_srs.len()];
+        let expected = parallel_msm_with(&scalars, &lagrange_srs, msm_window_size(scalars.len()));
+
+        assert_eq!(commitment, expected);
+        assert_eq!(
+            commitment,
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+
+        // a varying product takes the MSM
+        let prover = Prover::new(random_poly(3)).unwrap();
+        let witness = random_poly(3);
+        assert_eq!(
+            prover
+                .commit_lagrange_with_sum(&witness, &lagrange_srs, &sum)
+                .unwrap(),
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_commit_on_coset_domain() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let domain = Domain::new_coset(3, 2, FrElement::from(7)).unwrap();
+        let lagrange_srs = to_lagrange_basis_with_domain(&srs, &domain).unwrap();
+
+        let prover = Prover::new_with_domain(random_poly(7), &domain).unwrap();
+        let witness = random_poly(7);
+
+        assert_eq!(
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap(),
+            prover.commit_polynomial(&witness, &srs).unwrap()
+        );
+
+        // a polynomial larger than the domain is rejected
+        assert!(Prover::new_with_domain(random_poly(8), &domain).is_err());
+    }
+
+    #[test]
+    fn test_commit_evaluations_on_coset() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let domain = Domain::new_coset(3, 2, FrElement::from(7)).unwrap();
+        let polynomial = random_poly(7);
+        let evaluations = domain.evaluate(&polynomial).unwrap();
+
+        let coeff = polynomial
+            .coefficients()
+            .iter()
+            .map(|c| c.representative())
+            .collect::<Vec<_>>();
+        let expected = parallel_msm_with(&coeff, &srs, msm_window_size(coeff.len()));
+
+        assert_eq!(
+            Prover::commit_evaluations(&evaluations, domain.offset(), &srs).unwrap(),
+            expected
+        );
+        // interpolating the coset evaluations over the subgroup gives a different polynomial
+        assert_ne!(
+            Prover::commit_evaluations(&evaluations, None, &srs).unwrap(),
+            expected
+        );
+        assert!(Prover::commit_evaluations(&evaluations, domain.offset(), &srs[..15]).is_err());
+    }
+
+    #[test]
+    fn test_commit_lagrange_repr() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let reps = witness
+            .evaluate_fft(2, None)
+            .unwrap()
+            .iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            prover.commit_lagrange_repr(&reps, &lagrange_srs).unwrap(),
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+        assert!(prover
+            .commit_lagrange_repr(&reps[..8], &lagrange_srs)
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_lagrange_debug() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let (commitment, windows) = prover
+            .commit_lagrange_debug(&witness, &lagrange_srs)
+            .unwrap();
+        assert_eq!(
+            commitment,
+            prover.commit_lagrange(&witness, &lagrange_srs).unwrap()
+        );
+
+        // 16 scalars give 3-bit windows
+        let window_size = msm_window_size(16);
+        assert_eq!(window_size, 3);
+        let shifted = windows
+            .iter()
+            .enumerate()
+            .fold(G1Point::neutral_element(), |acc, (k, w)| {
+                acc.operate_with(&w.operate_with_self(U256::from_u64(1) << (k * window_size)))
+            });
+        assert_eq!(shifted, commitment);
+    }
+
+    #[test]
+    fn test_commit_and_open() {
+        use crate::{
+            srs::generate_srs_g2,
+            verifier::{verify, OpeningItem},
+        };
+
+        let srs = generate_srs(16, FrElement::from(42));
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let (commitment, value, proof) = prover.commit_and_open(&witness, &srs, b"seed").unwrap();
+        assert_eq!(
+            commitment,
+            prover.commit_polynomial(&witness, &srs).unwrap()
+        );
+
+        let item = OpeningItem {
+            point: fiat_shamir_challenge(b"seed", &commitment, 4),
+            commitment,
+            value,
+            proof,
+        };
+        assert!(verify(&item, &generate_srs_g2(&FrElement::from(42))));
+    }
+
+    #[test]
+    fn test_commit_concat() {
+        let srs = generate_srs(8, FrElement::from(42));
+
+        // (1 + 2x) ++ (3 + 4x + 5x^2) = 1 + 2x + 3x^2 + 4x^3 + 5x^4
+        let a = Polynomial::new(&[FrElement::from(1), FrElement::from(2)]);
+        let b = Polynomial::new(&[FrElement::from(3), FrElement::from(4), FrElement::from(5)]);
+        let manual = Polynomial::new(&(1..=5).map(FrElement::from).collect::<Vec<_>>());
+        assert_eq!(
+            Prover::commit_concat(&a, &b, &srs).unwrap(),
+            commit_coefficients(&manual, &srs).unwrap()
+        );
+
+        assert!(Prover::commit_concat(&a, &b, &srs[..4]).is_err());
+    }
+
+    #[test]
+    fn test_commit_bivariate() {
+        let (tau, sigma) = (FrElement::from(3), FrElement::from(5));
+        let srs = crate::srs::generate_bivariate_srs(2, 2, &tau, &sigma);
+
+        // 1 + 2y + 3x + 4xy at (3, 5) is 1 + 10 + 9 + 60 = 80
+        let coefficients = vec![
+            vec![FrElement::from(1), FrElement::from(2)],
+            vec![FrElement::from(3), FrElement::from(4)],
+        ];
+        assert_eq!(
+            commit_bivariate(&coefficients, &srs, 2).unwrap(),
+            srs[0].operate_with_self(80u64)
+        );
+
+        assert!(commit_bivariate(&coefficients, &srs[..3], 2).is_err());
+        assert!(commit_bivariate(&[vec![FrElement::one(); 3]], &srs, 2).is_err());
+    }
+
+    #[test]
+    fn test_commit_one() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let one = Polynomial::new(&[FrElement::one()]);
+
+        // monomial basis: only the constant term, against tau^0 * G
+        assert_eq!(
+            Prover::commit_one(&srs).unwrap(),
+            commit_coefficients(&one, &srs).unwrap()
+        );
+        assert_eq!(Prover::commit_one(&srs).unwrap(), crate::g1_generator());
+
+        // Lagrange basis: 1 evaluates to 1 everywhere, so every basis point is summed
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        assert_eq!(
+            crate::utils::srs_sum(&lagrange_srs),
+            Prover::commit_one(&srs).unwrap()
+        );
+
+        assert!(Prover::commit_one(&[]).is_err());
+    }
+
+    #[test]
+    fn test_commit_from_roots() {
+        let srs = generate_srs(8, FrElement::from(42));
+        let g1 = srs[0].clone();
+
+        // (x - 1)(x - 2)(x - 3) = x^3 - 6x^2 + 11x - 6
+        let roots = [FrElement::from(1), FrElement::from(2), FrElement::from(3)];
+        let explicit = Polynomial::new(&[
+            -FrElement::from(6),
+            FrElement::from(11),
+            -FrElement::from(6),
+            FrElement::one(),
+        ]);
+        assert_eq!(
+            Prover::commit_from_roots(&roots, &srs).unwrap(),
+            commit_coefficients(&explicit, &srs).unwrap()
+        );
+
+        // the subgroup of size 4 vanishes on x^4 - 1
+        let domain = Domain::new(2, 1).unwrap();
+        let subgroup = (0..4).map(|i| domain.element(i)).collect::<Vec<_>>();
+        assert_eq!(
+            Prover::commit_from_roots(&subgroup, &srs).unwrap(),
+            srs[4].operate_with(&g1.neg())
+        );
+
+        assert!(Prover::commit_from_roots(&subgroup, &srs[..4]).is_err());
+    }
+
+    /// Random polynomial and witness pairs across several domain sizes must commit the
+    /// same both ways. `TAUVSLAGRANGE_PROPERTY_ITERATIONS` sets the number of pairs per
+    /// size (default 4)
+    #[test]
+    fn test_commit_paths_agree_randomized() {
+        use rand::Rng;
+
+        let iterations = std::env::var("TAUVSLAGRANGE_PROPERTY_ITERATIONS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(4);
+        let mut rng = rand::thread_rng();
+
+        for n in [1, 2, 4, 8, 16] {
+            let srs = generate_srs(2 * n, random_fr());
+            let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+
+            // the prover polynomial fixes the domain; the witness may be any degree below
+            for _ in 0..iterations {
+                let prover = Prover::new(random_poly(n - 1)).unwrap();
+                let witness = random_poly(rng.gen_range(0..n));
+
+                assert_eq!(
+                    prover.commit_polynomial(&witness, &srs).unwrap(),
+                    prover.commit_lagrange(&witness, &lagrange_srs).unwrap(),
+                    "n = {}",
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_domain_elements() {
+        let poly = random_poly(7);
+        for domain in [
+            Domain::new(3, 2).unwrap(),
+            Domain::new_coset(3, 4, FrElement::from(7)).unwrap(),
+        ] {
+            let prover = Prover::new_with_domain(poly.clone(), &domain).unwrap();
+            let elements = prover.domain_elements();
+
+            assert_eq!(elements.len(), domain.size());
+            assert_eq!(elements[5], domain.element(5));
+            assert!(elements
+                .iter()
+                .zip(prover.evaluations())
+                .all(|(x, e)| poly.evaluate(x) == *e));
+        }
+    }
+
+    #[test]
+    fn test_evaluations() {
+        let poly = random_poly(7);
+        let prover = Prover::new(poly.clone()).unwrap();
+
+        assert_eq!(prover.evaluations(), poly.evaluate_fft(2, None).unwrap());
+    }
+
+    #[test]
+    fn test_from_lagrange_evals() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let poly = random_poly(7);
+        let witness = random_poly(7);
+
+        let from_coefficients = Prover::new(poly.clone()).unwrap();
+        let from_evals = Prover::from_lagrange_evals(poly.evaluate_fft(2, None).unwrap()).unwrap();
+        assert_eq!(
+            from_evals.commit_lagrange(&witness, &lagrange_srs).unwrap(),
+            from_coefficients
+                .commit_lagrange(&witness, &lagrange_srs)
+                .unwrap()
+        );
+        assert_eq!(
+            from_evals.commit_polynomial(&witness, &srs).unwrap(),
+            from_coefficients.commit_polynomial(&witness, &srs).unwrap()
+        );
+
+        assert!(Prover::from_lagrange_evals(vec![FrElement::one(); 6]).is_err());
+        assert!(Prover::from_lagrange_evals(vec![FrElement::one()]).is_err());
+    }
+
+    #[test]
+    fn test_msm_backends() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let expected = prover.commit_lagrange(&witness, &lagrange_srs).unwrap();
+        for backend in [
+            MsmBackend::Naive,
+            MsmBackend::Pippenger { window: None },
+            MsmBackend::Pippenger { window: Some(1) },
+            MsmBackend::Pippenger { window: Some(8) },
+            MsmBackend::SequentialPippenger { window: None },
+        ] {
+            assert_eq!(
+                prover
+                    .commit_lagrange_with_backend(&witness, &lagrange_srs, backend)
+                    .unwrap(),
+                expected
+            );
+            assert_eq!(
+                prover
+                    .commit_polynomial_with_backend(&witness, &srs, backend)
+                    .unwrap(),
+                expected
+            );
+        }
+
+        for window in [0, 64] {
+            for backend in [
+                MsmBackend::Pippenger {
+                    window: Some(window),
+                },
+                MsmBackend::SequentialPippenger {
+                    window: Some(window),
+                },
+            ] {
+                assert!(matches!(
+                    prover.commit_lagrange_with_backend(&witness, &lagrange_srs, backend),
+                    Err(ProverError::InvalidMsmWindow(w)) if w == window
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_commit_batch() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witnesses = (0..4).map(|_| random_poly(7)).collect::<Vec<_>>();
+
+        let outer = prover
+            .commit_batch(&witnesses, &lagrange_srs, BatchParallelism::Outer)
+            .unwrap();
+        let inner = prover
+            .commit_batch(&witnesses, &lagrange_srs, BatchParallelism::Inner)
+            .unwrap();
+
+        assert_eq!(outer, inner);
+        assert_eq!(
+            inner[2],
+            prover
+                .commit_lagrange(&witnesses[2], &lagrange_srs)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_commit_polynomial_profiled() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        let (commitment, phase) = prover.commit_polynomial_profiled(&witness, &srs).unwrap();
+        assert_eq!(
+            commitment,
+            prover.commit_polynomial(&witness, &srs).unwrap()
+        );
+        assert!(matches!(
+            phase,
+            Phase::Fft | Phase::Interpolation | Phase::Msm
+        ));
+
+        assert!(prover
+            .commit_polynomial_profiled(&witness, &srs[..8])
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_batch_lagrange_shared() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let witnesses = (0..4).map(|_| random_poly(7)).collect::<Vec<_>>();
+
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let expected = witnesses
+            .iter()
+            .map(|w| prover.commit_lagrange(w, &lagrange_srs).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prover
+                .commit_batch_lagrange_shared(&witnesses, &lagrange_srs)
+                .unwrap(),
+            expected
+        );
+
+        // coset domains share the offset powers as well
+        let domain = Domain::new_coset(3, 2, FrElement::from(7)).unwrap();
+        let lagrange_srs = to_lagrange_basis_with_domain(&srs, &domain).unwrap();
+        let prover = Prover::new_with_domain(random_poly(7), &domain).unwrap();
+        let expected = witnesses
+            .iter()
+            .map(|w| prover.commit_lagrange(w, &lagrange_srs).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prover
+                .commit_batch_lagrange_shared(&witnesses, &lagrange_srs)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_commit_auto() {
+        let srs = generate_srs(64, FrElement::from(42));
+        let poly = random_poly(3);
+        let witness = random_poly(9);
+
+        // both pad to 16 coefficients, so the product needs 32 powers of the 64
+        assert_eq!(
+            commit_auto(&poly, &witness, &srs).unwrap(),
+            commit_coefficients(&poly.mul_with_ref(&witness), &srs).unwrap()
+        );
+        assert_eq!(
+            commit_auto(&witness, &poly, &srs).unwrap(),
+            commit_coefficients(&poly.mul_with_ref(&witness), &srs).unwrap()
+        );
+        assert!(commit_auto(&poly, &witness, &srs[..31]).is_err());
+    }
+
+    #[test]
+    fn test_commit_derivative() {
+        let srs = generate_srs(8, FrElement::from(42));
+
+        // p = 5 + 3x + 2x^2 + 7x^3, p' = 3 + 4x + 21x^2
+        let poly = Polynomial::new(&[
+            FrElement::from(5),
+            FrElement::from(3),
+            FrElement::from(2),
+            FrElement::from(7),
+        ]);
+        let derivative =
+            Polynomial::new(&[FrElement::from(3), FrElement::from(4), FrElement::from(21)]);
+        let prover = Prover::new(poly).unwrap();
+
+        assert_eq!(
+            prover.commit_derivative(&srs).unwrap(),
+            commit_coefficients(&derivative, &srs).unwrap()
+        );
+        // the derivative only needs three powers
+        assert!(prover.commit_derivative(&srs[..3]).is_ok());
+
+        let constant = Prover::new(Polynomial::new(&[FrElement::from(9)])).unwrap();
+        assert_eq!(
+            constant.commit_derivative(&srs).unwrap(),
+            G1Point::neutral_element()
+        );
+    }
+
+    #[test]
+    fn test_commit_reciprocal() {
+        let srs = generate_srs(8, FrElement::from(42));
+
+        // p = 5 + 3x + 2x^2 + 7x^3, x^3 * p(1/x) = 7 + 2x + 3x^2 + 5x^3
+        let poly = Polynomial::new(&[
+            FrElement::from(5),
+            FrElement::from(3),
+            FrElement::from(2),
+            FrElement::from(7),
+        ]);
+        let reversed = Polynomial::new(&[
+            FrElement::from(7),
+            FrElement::from(2),
+            FrElement::from(3),
+            FrElement::from(5),
+        ]);
+        assert_eq!(
+            Prover::new(poly).unwrap().commit_reciprocal(&srs).unwrap(),
+            commit_coefficients(&reversed, &srs).unwrap()
+        );
+
+        // p = 3x + 2x^2: the zero constant term drops the reciprocal to 2 + 3x
+        let no_constant =
+            Polynomial::new(&[FrElement::zero(), FrElement::from(3), FrElement::from(2)]);
+        let reversed = Polynomial::new(&[FrElement::from(2), FrElement::from(3)]);
+        let prover = Prover::new(no_constant).unwrap();
+        assert_eq!(
+            prover.commit_reciprocal(&srs).unwrap(),
+            commit_coefficients(&reversed, &srs).unwrap()
+        );
+        assert!(prover.commit_reciprocal(&srs[..2]).is_ok());
+    }
+
+    #[test]
+    fn test_open_batch() {
+        let srs = generate_srs(16, FrElement::from(42));
+        let poly = random_poly(7);
+
+        // a single point matches the plain opening
+        let point = FrElement::from(11);
+        let (values, proof) = open_batch(&poly, std::slice::from_ref(&point), &srs).unwrap();
+        assert_eq!(
+            (values[0].clone(), proof),
+            open(&poly, &point, &srs).unwrap()
+        );
+
+        let points = [FrElement::from(3), FrElement::from(5), FrElement::from(9)];
+        let (values, _) = open_batch(&poly, &points, &srs).unwrap();
+        assert_eq!(values, poly.evaluate_slice(&points));
+
+        let duplicated = [FrElement::from(3), FrElement::from(5), FrElement::from(3)];
+        assert!(matches!(
+            open_batch(&poly, &duplicated, &srs),
+            Err(ProverError::DuplicateOpeningPoints)
+        ));
+    }
+
+    #[test]
+    fn test_commit_chunked() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let commit = |data: &[FrElement]| {
+            let scalars = data.iter().map(|e| e.representative()).collect::<Vec<_>>();
+            MsmBackend::Naive.msm(&scalars, &lagrange_srs).unwrap()
+        };
+
+        // one chunk is the plain commitment to the data
+        let first = random_field_elements(16);
+        let (commitment, _) = Prover::commit_chunked(&first, &lagrange_srs).unwrap();
+        assert_eq!(commitment, commit(&first));
+
+        // more chunks fold with powers of the challenge, the last one zero-padded
+        let second = random_field_elements(5);
+        let data = [first.clone(), second.clone()].concat();
+        let (commitment, r) = Prover::commit_chunked(&data, &lagrange_srs).unwrap();
+        let chunk_commitments = [commit(&first), commit(&second)];
+        assert_eq!(r, chunked_challenge(&chunk_commitments));
+        assert_eq!(
+            commitment,
+            chunk_commitments[0].operate_with(&scalar_mul(&chunk_commitments[1], &r))
+        );
+        // the challenge is derived, not drawn
+        assert_eq!(
+            Prover::commit_chunked(&data, &lagrange_srs).unwrap(),
+            (commitment, r)
+        );
+
+        assert!(Prover::commit_chunked(&data, &[]).is_err());
+    }
+
+    #[test]
+    fn test_commit_multilinear() {
+        let srs = generate_srs(16, FrElement::from(42));
+
+        // f(x0, x1, x2) over {0, 1}^3
+        let evals = (1..=8).map(FrElement::from).collect::<Vec<_>>();
+        let commitment = Prover::commit_multilinear(&evals, &srs).unwrap();
+
+        let expected = evals
+            .iter()
+            .zip(&srs)
+            .fold(G1Point::neutral_element(), |acc, (e, p)| {
+                acc.operate_with(&p.operate_with_self(e.representative()))
+            });
+        assert_eq!(commitment, expected);
+
+        assert!(Prover::commit_multilinear(&evals[..6], &srs).is_err());
+        assert!(Prover::commit_multilinear(&evals, &srs[..4]).is_err());
+    }
+
+    #[test]
+    fn test_commit_lagrange_fn() {
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+
+        // w_i = i^2 + 1
+        let reps = (0..16u64)
+            .map(|i| U256::from_u64(i * i + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prover
+                .commit_lagrange_fn(|i| FrElement::from((i * i + 1) as u64), &lagrange_srs)
+                .unwrap(),
+            prover.commit_lagrange_repr(&reps, &lagrange_srs).unwrap()
+        );
+
+        // a domain spanning several chunks of commit_lagrange_iter
+        let n = 2 * ITER_CHUNK_SIZE;
+        let prover = Prover::new(random_poly(n / 2 - 1)).unwrap();
+        let lagrange_srs = small_srs()
+            .iter()
+            .cycle()
+            .take(n)
+            .cloned()
+            .collect::<Vec<_>>();
+        let reps = (0..n as u64)
+            .map(|i| U256::from_u64(i * i + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            prover
+                .commit_lagrange_fn(|i| FrElement::from((i * i + 1) as u64), &lagrange_srs)
+                .unwrap(),
+            prover.commit_lagrange_repr(&reps, &lagrange_srs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_commit_lagrange_iter() {
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+
+        let expected = prover.commit_lagrange(&witness, &lagrange_srs).unwrap();
+
+        let witness_eval = witness.evaluate_fft(2, None).unwrap();
+        let commitment = prover
+            .commit_lagrange_iter(witness_eval.into_iter(), &lagrange_srs)
+            .unwrap();
+
+        assert_eq!(commitment, expected);
+
+        // a short iterator is rejected
+        let short = witness.evaluate_fft(1, None).unwrap();
+        assert!(prover
+            .commit_lagrange_iter(short.into_iter(), &lagrange_srs)
+            .is_err());
+    }
+
+    #[test]
+    fn test_commit_lagrange_iter_across_chunks() {
+        // a domain of several chunks; any points serve to compare the two commit paths
+        let n = 2 * ITER_CHUNK_SIZE;
+        let prover = Prover::new(random_poly(n / 2 - 1)).unwrap();
+        let lagrange_srs = small_srs()
+            .iter()
+            .cycle()
+            .take(n)
+            .cloned()
+            .collect::<Vec<_>>();
+        let evals = random_field_elements(n);
+        let reps = evals.iter().map(|e| e.representative()).collect::<Vec<_>>();
+        let expected = prover.commit_lagrange_repr(&reps, &lagrange_srs).unwrap();
+
+        // from_fn has no exact size hint, unlike the Vec iterators above
+        let mut stream = evals.iter().cloned();
+        let commitment = prover
+            .commit_lagrange_iter(std::iter::from_fn(|| stream.next()), &lagrange_srs)
+            .unwrap();
+        assert_eq!(commitment, expected);
 
-        // Compute the multi-scalar multiplication in parallel
-        Ok(parallel_msm_with(&coeff, pwrs_tau, window_size))
+        let mut short = evals[1..].iter().cloned();
+        assert!(prover
+            .commit_lagrange_iter(std::iter::from_fn(|| short.next()), &lagrange_srs)
+            .is_err());
     }
 }
@@ -1,30 +1,61 @@
+use std::io::{Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use lambdaworks_math::{
+    cyclic_group::IsGroup,
     elliptic_curve::{
-        short_weierstrass::curves::bls12_381::curve::BLS12381Curve, traits::IsEllipticCurve,
+        short_weierstrass::curves::bls12_381::{
+            curve::{BLS12381Curve, BLS12381FieldElement},
+            field_extension::BLS12381FieldModulus,
+            twist::BLS12381TwistCurve,
+        },
+        traits::IsEllipticCurve,
     },
-    unsigned_integer::element::UnsignedInteger,
+    field::element::FieldElement,
+    field::fields::montgomery_backed_prime_fields::IsModulus,
+    traits::ByteConversion,
+    unsigned_integer::element::{UnsignedInteger, U384},
 };
 use serde::{Deserialize, Serialize};
 
-use crate::G1Point;
+use crate::{
+    prover::sum_points,
+    srs::{srs_digest, CommitmentKey, TaggedSrs},
+    utils::Basis,
+    verifier::verify_srs_pair,
+    G1Point, G2Point,
+};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SerializedSRS {
     pub points: Vec<(String, String)>,
 }
 
 impl From<Vec<G1Point>> for SerializedSRS {
     fn from(srs: Vec<G1Point>) -> Self {
-        let affined = srs
+        SerializedSRS::from_points_with_endianness(&srs, false)
+    }
+}
+
+impl SerializedSRS {
+    /// Serialize points as affine hex coordinates.
+    /// Big endian is the library's default `0x...` output; little endian writes the
+    /// 48 coordinate bytes least significant first, as some external tools expect
+    pub fn from_points_with_endianness(points: &[G1Point], little_endian: bool) -> Self {
+        let affined = points
             .iter()
-            .map(|p| (p.to_affine().x().to_string(), p.to_affine().y().to_string()))
+            .map(|p| {
+                let affine = p.to_affine();
+                (
+                    encode_coordinate(affine.x(), little_endian),
+                    encode_coordinate(affine.y(), little_endian),
+                )
+            })
             .collect::<Vec<_>>();
 
         SerializedSRS { points: affined }
     }
-}
 
-impl SerializedSRS {
     pub fn to_ec_points(self) -> Vec<G1Point> {
         self.points
             .iter()
@@ -40,20 +71,785 @@ impl SerializedSRS {
             })
             .collect()
     }
+
+    /// Parse the points written by [`SerializedSRS::from_points_with_endianness`].
+    /// Fails instead of panicking if a coordinate can't be decoded or a point is not
+    /// on the curve, which is what reading a file with the wrong endianness produces
+    pub fn to_ec_points_with_endianness(
+        self,
+        little_endian: bool,
+    ) -> Result<Vec<G1Point>, Box<dyn std::error::Error>> {
+        self.to_ec_points_checked(little_endian, false)
+    }
+
+    /// Like [`SerializedSRS::to_ec_points_with_endianness`], optionally rejecting
+    /// coordinates that are not canonically reduced (`>= p`). Non-canonical encodings
+    /// decode to the same point, but break byte-level reproducibility and interop
+    pub fn to_ec_points_checked(
+        self,
+        little_endian: bool,
+        reject_non_canonical: bool,
+    ) -> Result<Vec<G1Point>, Box<dyn std::error::Error>> {
+        self.points
+            .iter()
+            .map(|(x, y)| {
+                let x = decode_coordinate(x, little_endian, reject_non_canonical)?;
+                let y = decode_coordinate(y, little_endian, reject_non_canonical)?;
+
+                <BLS12381Curve as IsEllipticCurve>::create_point_from_affine(x, y)
+                    .map_err(|err| format!("Invalid SRS point: {:?}", err).into())
+            })
+            .collect()
+    }
+}
+
+impl SerializedSRS {
+    /// Indices at which the two SRS hold different points. Points are compared after
+    /// decoding (big endian), so differently written encodings of the same point match;
+    /// an undecodable point differs from everything, and indices present in only one
+    /// SRS are reported as differing
+    pub fn diff(&self, other: &SerializedSRS) -> Vec<usize> {
+        let decode = |(x, y): &(String, String)| {
+            let x = decode_coordinate(x, false, false).ok()?;
+            let y = decode_coordinate(y, false, false).ok()?;
+            <BLS12381Curve as IsEllipticCurve>::create_point_from_affine(x, y).ok()
+        };
+
+        (0..self.points.len().max(other.points.len()))
+            .filter(|&i| {
+                match (
+                    self.points.get(i).and_then(decode),
+                    other.points.get(i).and_then(decode),
+                ) {
+                    (Some(a), Some(b)) => a != b,
+                    _ => true,
+                }
+            })
+            .collect()
+    }
 }
 
 impl SerializedSRS {
+    /// Write the points as JSON. Paths ending in `.gz` are gzip-compressed
     pub fn dump(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
         let serialized_data = serde_json::to_string(&self.points)?;
-        std::fs::write(file_path, serialized_data)?;
+
+        if is_gzip(file_path) {
+            let file = std::fs::File::create(file_path)?;
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder.write_all(serialized_data.as_bytes())?;
+            encoder.finish()?;
+        } else {
+            std::fs::write(file_path, serialized_data)?;
+        }
 
         Ok(())
     }
 
+    /// Read points written by [`SerializedSRS::dump`]. Paths ending in `.gz` are decompressed
     pub fn load(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let serialized_data = std::fs::read_to_string(file_path)?;
-        let points: Vec<(String, String)> = serde_json::from_str(&serialized_data)?;
+        let file = std::fs::File::open(file_path)?;
+        SerializedSRS::parse(file, is_gzip(file_path))
+            .map_err(|err| err as Box<dyn std::error::Error>)
+    }
+
+    /// Like [`SerializedSRS::load`], but picks the format from the file contents instead
+    /// of the extension: gzip by its magic bytes, plain JSON by its opening `[`. These
+    /// are the only formats [`SerializedSRS::dump`] writes; anything else is an error
+    pub fn load_auto(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+        let bytes = std::fs::read(file_path)?;
+        let gzip = if bytes.starts_with(&GZIP_MAGIC) {
+            true
+        } else if bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[') {
+            false
+        } else {
+            return Err(format!("Unrecognized SRS format: {}", file_path).into());
+        };
+
+        SerializedSRS::parse(bytes.as_slice(), gzip)
+            .map_err(|err| err as Box<dyn std::error::Error>)
+    }
+
+    /// Load the points of a file written by [`SerializedSRS::dump`], tagged with the basis
+    /// named by the file: [`Basis::Lagrange`] if the file name has the word `lagrange`
+    /// (as in the CLI's `lagrange_srs.json`), [`Basis::Monomial`] otherwise
+    pub fn load_tagged(file_path: &str) -> Result<TaggedSrs, Box<dyn std::error::Error>> {
+        let file_name = std::path::Path::new(file_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let basis = if file_name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| word.eq_ignore_ascii_case("lagrange"))
+        {
+            Basis::Lagrange
+        } else {
+            Basis::Monomial
+        };
+
+        Ok(TaggedSrs::new(
+            basis,
+            SerializedSRS::load_with_endianness(file_path, false)?,
+        ))
+    }
+
+    /// Load the points of a file written by [`SerializedSRS::dump`] and check that their
+    /// [`srs_digest`] is `expected_digest`, failing on a corrupted or swapped file
+    pub fn load_verified(
+        file_path: &str,
+        expected_digest: [u8; 32],
+    ) -> Result<Vec<G1Point>, Box<dyn std::error::Error>> {
+        let points = SerializedSRS::load_with_endianness(file_path, false)?;
+        if srs_digest(&points) != expected_digest {
+            return Err(format!("SRS digest mismatch for {}", file_path).into());
+        }
+        Ok(points)
+    }
+
+    /// Load the points of a file written by [`SerializedSRS::dump`] and, when a G2 SRS is
+    /// given, run [`verify_srs_pair`] on them. Returns the points and the check result,
+    /// `None` without a G2 SRS. A mismatch (files from different setups) is reported
+    /// rather than failed, so the caller decides how loudly to complain
+    pub fn load_paired(
+        file_path: &str,
+        g2: Option<&[G2Point]>,
+    ) -> Result<(Vec<G1Point>, Option<bool>), Box<dyn std::error::Error>> {
+        let points = SerializedSRS::load_with_endianness(file_path, false)?;
+        let paired = g2.map(|g2| verify_srs_pair(&points, g2));
+        Ok((points, paired))
+    }
+
+    /// Async [`SerializedSRS::load`]: the file is read with `tokio::fs` and the
+    /// CPU-bound decompression and JSON parsing run on the blocking pool
+    #[cfg(feature = "async")]
+    pub async fn load_async(
+        file_path: &str,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let bytes = tokio::fs::read(file_path).await?;
+        let gzip = is_gzip(file_path);
+        tokio::task::spawn_blocking(move || SerializedSRS::parse(bytes.as_slice(), gzip)).await?
+    }
+
+    /// Fetch an SRS file in the [`SerializedSRS::dump`] format (gzip when the URL path
+    /// ends in `.gz`) and parse the response as it streams in, checking the
+    /// [`srs_digest`] of the points when one is given
+    #[cfg(feature = "http")]
+    pub fn load_url(
+        url: &str,
+        expected_digest: Option<[u8; 32]>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let response = reqwest::blocking::get(url)?.error_for_status()?;
+        let gzip = is_gzip(response.url().path());
+        let srs = SerializedSRS::parse(response, gzip)
+            .map_err(|err| err as Box<dyn std::error::Error>)?;
+
+        if let Some(expected) = expected_digest {
+            if srs_digest(&srs.clone().to_ec_points_with_endianness(false)?) != expected {
+                return Err(format!("SRS digest mismatch for {}", url).into());
+            }
+        }
+        Ok(srs)
+    }
+
+    fn parse<R: Read>(
+        reader: R,
+        gzip: bool,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let points: Vec<(String, String)> = if gzip {
+            serde_json::from_reader(std::io::BufReader::new(GzDecoder::new(reader)))?
+        } else {
+            serde_json::from_reader(std::io::BufReader::new(reader))?
+        };
 
         Ok(SerializedSRS { points })
     }
+
+    /// Load a file and parse its points with the given endianness
+    pub fn load_with_endianness(
+        file_path: &str,
+        little_endian: bool,
+    ) -> Result<Vec<G1Point>, Box<dyn std::error::Error>> {
+        SerializedSRS::load(file_path)?.to_ec_points_with_endianness(little_endian)
+    }
+}
+
+/// Incremental writer producing the same file as [`SerializedSRS::dump`] (plain or
+/// `.gz`), one batch of points at a time, so the full SRS and its serialized strings
+/// never have to be held together
+pub struct SrsWriter {
+    sink: SrsSink,
+    empty: bool,
+}
+
+enum SrsSink {
+    Plain(std::io::BufWriter<std::fs::File>),
+    Gzip(GzEncoder<std::io::BufWriter<std::fs::File>>),
+}
+
+impl SrsWriter {
+    pub fn create(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = std::io::BufWriter::new(std::fs::File::create(file_path)?);
+        let mut sink = if is_gzip(file_path) {
+            SrsSink::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            SrsSink::Plain(file)
+        };
+        sink.writer().write_all(b"[")?;
+
+        Ok(SrsWriter { sink, empty: true })
+    }
+
+    /// Append points in the big-endian affine hex encoding
+    pub fn write_points(&mut self, points: &[G1Point]) -> Result<(), Box<dyn std::error::Error>> {
+        let serialized = SerializedSRS::from_points_with_endianness(points, false);
+        for point in &serialized.points {
+            if !self.empty {
+                self.sink.writer().write_all(b",")?;
+            }
+            serde_json::to_writer(self.sink.writer(), point)?;
+            self.empty = false;
+        }
+        Ok(())
+    }
+
+    /// Close the JSON array and flush (and finish the gzip stream)
+    pub fn finish(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.sink.writer().write_all(b"]")?;
+        match self.sink {
+            SrsSink::Plain(mut file) => file.flush()?,
+            SrsSink::Gzip(encoder) => encoder.finish()?.flush()?,
+        }
+        Ok(())
+    }
+}
+
+impl SrsSink {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            SrsSink::Plain(file) => file,
+            SrsSink::Gzip(encoder) => encoder,
+        }
+    }
+}
+
+/// A single commitment in the affine hex encoding of [`SerializedSRS`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SerializedCommitment {
+    /// Affine coordinates, `None` for the point at infinity (which has no affine form)
+    pub point: Option<(String, String)>,
+}
+
+impl From<&G1Point> for SerializedCommitment {
+    fn from(commitment: &G1Point) -> Self {
+        if *commitment == G1Point::neutral_element() {
+            return SerializedCommitment { point: None };
+        }
+
+        let affine = commitment.to_affine();
+        SerializedCommitment {
+            point: Some((
+                encode_coordinate(affine.x(), false),
+                encode_coordinate(affine.y(), false),
+            )),
+        }
+    }
+}
+
+impl SerializedCommitment {
+    pub fn to_ec_point(&self) -> Result<G1Point, Box<dyn std::error::Error>> {
+        let Some((x, y)) = &self.point else {
+            return Ok(G1Point::neutral_element());
+        };
+
+        let x = decode_coordinate(x, false, false)?;
+        let y = decode_coordinate(y, false, false)?;
+        <BLS12381Curve as IsEllipticCurve>::create_point_from_affine(x, y)
+            .map_err(|err| format!("Invalid commitment point: {:?}", err).into())
+    }
+
+    /// Write the commitment as JSON
+    pub fn dump(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(file_path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Read a commitment written by [`SerializedCommitment::dump`]
+    pub fn load(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(file_path)?)?)
+    }
+}
+
+/// Header identifying a [`CommitmentKeyBundle`] file and its layout version
+const BUNDLE_HEADER: &str = "tauvslagrange-commitment-key-v1";
+
+/// A complete setup in one file: the powers of tau, the Lagrange SRS derived from them
+/// and optionally the G2 SRS `[G2, tau * G2]`, instead of separate `srs.json` and
+/// `lagrange_srs.json` files
+#[derive(Clone, Debug)]
+pub struct CommitmentKeyBundle {
+    pub key: CommitmentKey,
+    pub g2: Option<Vec<G2Point>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleFile {
+    header: String,
+    powers_of_tau: Vec<(String, String)>,
+    lagrange_srs: Vec<(String, String)>,
+    /// Affine G2 coordinates as `[x0, x1, y0, y1]` over the quadratic extension
+    g2: Option<Vec<[String; 4]>>,
+}
+
+impl CommitmentKeyBundle {
+    pub fn new(key: CommitmentKey, g2: Option<Vec<G2Point>>) -> Self {
+        CommitmentKeyBundle { key, g2 }
+    }
+
+    /// Write the bundle as JSON behind a format header
+    pub fn dump(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let g2 = self
+            .g2
+            .as_ref()
+            .map(|points| points.iter().map(encode_g2_point).collect());
+        let file = BundleFile {
+            header: BUNDLE_HEADER.to_string(),
+            powers_of_tau: SerializedSRS::from(self.key.powers_of_tau.clone()).points,
+            lagrange_srs: SerializedSRS::from(self.key.lagrange_srs.clone()).points,
+            g2,
+        };
+
+        std::fs::write(file_path, serde_json::to_string(&file)?)?;
+        Ok(())
+    }
+
+    /// Read a bundle written by [`CommitmentKeyBundle::dump`] and check it is consistent:
+    /// both SRS have the same length, the Lagrange points sum to the generator
+    /// `powers_of_tau[0]` (the L_i sum to 1), and the G2 SRS, if present, encodes the
+    /// same tau as the powers of tau (pairing check)
+    pub fn load(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file: BundleFile = serde_json::from_str(&std::fs::read_to_string(file_path)?)?;
+        if file.header != BUNDLE_HEADER {
+            return Err(format!("Unknown commitment key header: {}", file.header).into());
+        }
+
+        let powers_of_tau = SerializedSRS {
+            points: file.powers_of_tau,
+        }
+        .to_ec_points_with_endianness(false)?;
+        let lagrange_srs = SerializedSRS {
+            points: file.lagrange_srs,
+        }
+        .to_ec_points_with_endianness(false)?;
+        let g2 = file
+            .g2
+            .map(|points| {
+                points
+                    .iter()
+                    .map(decode_g2_point)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        if powers_of_tau.is_empty()
+            || lagrange_srs.len() != powers_of_tau.len()
+            || sum_points(&lagrange_srs) != powers_of_tau[0]
+        {
+            return Err("Lagrange SRS does not match the powers of tau".into());
+        }
+        if let Some(g2) = &g2 {
+            if !verify_srs_pair(&powers_of_tau, g2) {
+                return Err("G2 SRS does not match the powers of tau".into());
+            }
+        }
+
+        Ok(CommitmentKeyBundle {
+            key: CommitmentKey {
+                powers_of_tau,
+                lagrange_srs,
+            },
+            g2,
+        })
+    }
+}
+
+/// G2 SRS `[G2, tau * G2]` as JSON, each point as its affine coordinates `[x0, x1, y0, y1]`
+/// over the quadratic extension, for checking a G1 SRS file against with
+/// [`SerializedSRS::load_paired`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SerializedG2SRS {
+    points: Vec<[String; 4]>,
+}
+
+impl From<&[G2Point]> for SerializedG2SRS {
+    fn from(srs: &[G2Point]) -> Self {
+        SerializedG2SRS {
+            points: srs.iter().map(encode_g2_point).collect(),
+        }
+    }
+}
+
+impl SerializedG2SRS {
+    pub fn to_ec_points(&self) -> Result<Vec<G2Point>, Box<dyn std::error::Error>> {
+        self.points.iter().map(decode_g2_point).collect()
+    }
+
+    pub fn dump(&self, file_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(file_path, serde_json::to_string(&self.points)?)?;
+        Ok(())
+    }
+
+    pub fn load(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let points = serde_json::from_str(&std::fs::read_to_string(file_path)?)?;
+        Ok(SerializedG2SRS { points })
+    }
+}
+
+fn encode_g2_point(point: &G2Point) -> [String; 4] {
+    let point = point.to_affine();
+    let [x0, x1] = point.x().value();
+    let [y0, y1] = point.y().value();
+    [x0, x1, y0, y1].map(|c| encode_coordinate(c, false))
+}
+
+fn decode_g2_point([x0, x1, y0, y1]: &[String; 4]) -> Result<G2Point, Box<dyn std::error::Error>> {
+    let x = FieldElement::new([
+        decode_coordinate(x0, false, false)?,
+        decode_coordinate(x1, false, false)?,
+    ]);
+    let y = FieldElement::new([
+        decode_coordinate(y0, false, false)?,
+        decode_coordinate(y1, false, false)?,
+    ]);
+    <BLS12381TwistCurve as IsEllipticCurve>::create_point_from_affine(x, y)
+        .map_err(|err| format!("Invalid G2 point: {:?}", err).into())
+}
+
+fn is_gzip(file_path: &str) -> bool {
+    file_path.ends_with(".gz")
+}
+
+fn encode_coordinate(coordinate: &BLS12381FieldElement, little_endian: bool) -> String {
+    if !little_endian {
+        return coordinate.to_string();
+    }
+
+    let hex = coordinate
+        .to_bytes_le()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    format!("0x{}", hex)
+}
+
+fn decode_coordinate(
+    coordinate: &str,
+    little_endian: bool,
+    reject_non_canonical: bool,
+) -> Result<BLS12381FieldElement, Box<dyn std::error::Error>> {
+    let value = if !little_endian {
+        UnsignedInteger::from_hex(coordinate)
+            .map_err(|err| format!("Invalid hex coordinate: {:?}", err))?
+    } else {
+        let hex = coordinate.strip_prefix("0x").unwrap_or(coordinate);
+        if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+            return Err(format!("Invalid hex coordinate: {}", coordinate).into());
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        U384::from_bytes_le(&bytes).map_err(|err| format!("Invalid coordinate bytes: {:?}", err))?
+    };
+
+    if reject_non_canonical && value >= BLS12381FieldModulus::MODULUS {
+        return Err(format!("Non-canonical coordinate: {}", coordinate).into());
+    }
+
+    Ok((&value).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrElement;
+
+    use crate::{
+        prover::Prover,
+        srs::generate_srs,
+        test_fixtures::{small_srs, TempPath, SMALL_SRS_TAU},
+        utils::{random_poly, to_lagrange_basis},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_endianness_roundtrip() {
+        let srs = small_srs();
+
+        for little_endian in [false, true] {
+            let path = TempPath::new(&format!("test_endianness_roundtrip_{}.json", little_endian));
+            let path = path.as_str();
+
+            SerializedSRS::from_points_with_endianness(srs, little_endian)
+                .dump(path)
+                .unwrap();
+
+            let loaded = SerializedSRS::load_with_endianness(path, little_endian).unwrap();
+            assert_eq!(loaded, srs);
+
+            // reading with the other endianness is an error, not a panic
+            assert!(SerializedSRS::load_with_endianness(path, !little_endian).is_err());
+        }
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let srs = SerializedSRS::from(small_srs().to_vec());
+        let plain = TempPath::new("test_gzip_roundtrip.json");
+        let gzip = TempPath::new("test_gzip_roundtrip.json.gz");
+        let (plain, gzip) = (plain.as_str(), gzip.as_str());
+
+        srs.dump(plain).unwrap();
+        srs.dump(gzip).unwrap();
+
+        // the compressed file is not plain JSON
+        assert!(std::fs::read_to_string(gzip).is_err());
+        assert_eq!(
+            SerializedSRS::load(gzip).unwrap().to_ec_points(),
+            SerializedSRS::load(plain).unwrap().to_ec_points()
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let srs = SerializedSRS::from(small_srs().to_vec());
+        assert!(srs.diff(&srs.clone()).is_empty());
+
+        let mut perturbed = small_srs().to_vec();
+        perturbed[3] = perturbed[3].operate_with(&perturbed[0]);
+        assert_eq!(srs.diff(&SerializedSRS::from(perturbed)), vec![3]);
+
+        let short = SerializedSRS::from(small_srs()[..6].to_vec());
+        assert_eq!(srs.diff(&short), vec![6, 7]);
+    }
+
+    #[test]
+    fn test_load_tagged() {
+        let srs = SerializedSRS::from(small_srs().to_vec());
+        // the basis is taken from the file name
+        for (name, basis) in [
+            ("test_load_tagged_srs.json", Basis::Monomial),
+            ("test_load_tagged_lagrange_srs.json", Basis::Lagrange),
+        ] {
+            let path = TempPath::new(name);
+            let path = path.as_str();
+            srs.dump(path).unwrap();
+
+            let tagged = SerializedSRS::load_tagged(path).unwrap();
+            assert_eq!(tagged.basis, basis);
+            assert_eq!(tagged.points, small_srs());
+        }
+    }
+
+    #[test]
+    fn test_load_auto() {
+        let srs = SerializedSRS::from(small_srs().to_vec());
+        // the extensions are deliberately wrong: only the contents decide
+        let plain = TempPath::new("test_load_auto_plain.gz");
+        let gzip = TempPath::new("test_load_auto_gzip.json");
+        let (plain, gzip) = (plain.as_str(), gzip.as_str());
+        std::fs::write(plain, serde_json::to_string(&srs.points).unwrap()).unwrap();
+        let gzip_staging = format!("{}.gz", gzip);
+        srs.dump(&gzip_staging).unwrap();
+        std::fs::rename(&gzip_staging, gzip).unwrap();
+
+        for path in [plain, gzip] {
+            assert_eq!(
+                SerializedSRS::load_auto(path).unwrap().to_ec_points(),
+                small_srs()
+            );
+        }
+
+        std::fs::write(plain, b"x,y\n1,2\n").unwrap();
+        assert!(SerializedSRS::load_auto(plain).is_err());
+    }
+
+    #[test]
+    fn test_load_verified() {
+        let srs = small_srs();
+        let digest = srs_digest(srs);
+        let path = TempPath::new("test_load_verified.json");
+        let path = path.as_str();
+
+        SerializedSRS::from(srs.to_vec()).dump(path).unwrap();
+        assert_eq!(SerializedSRS::load_verified(path, digest).unwrap(), srs);
+
+        // swap two points: still a valid SRS file, but not the expected one
+        let mut tampered = srs.to_vec();
+        tampered.swap(1, 2);
+        SerializedSRS::from(tampered).dump(path).unwrap();
+        assert!(SerializedSRS::load_verified(path, digest).is_err());
+    }
+
+    #[test]
+    fn test_load_paired() {
+        use crate::srs::generate_srs_g2;
+
+        let srs = small_srs();
+        let path = TempPath::new("test_load_paired.json");
+        let path = path.as_str();
+        let g2_path = TempPath::new("test_load_paired_g2.json");
+        let g2_path = g2_path.as_str();
+
+        SerializedSRS::from(srs.to_vec()).dump(path).unwrap();
+        let g2 = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU));
+        SerializedG2SRS::from(g2.as_slice()).dump(g2_path).unwrap();
+        let g2 = SerializedG2SRS::load(g2_path)
+            .unwrap()
+            .to_ec_points()
+            .unwrap();
+
+        let (points, paired) = SerializedSRS::load_paired(path, Some(&g2)).unwrap();
+        assert_eq!(points, srs);
+        assert_eq!(paired, Some(true));
+        assert_eq!(SerializedSRS::load_paired(path, None).unwrap().1, None);
+
+        let other = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU + 1));
+        assert_eq!(
+            SerializedSRS::load_paired(path, Some(&other)).unwrap().1,
+            Some(false)
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_load_url() {
+        let srs = small_srs();
+        let body = serde_json::to_string(&SerializedSRS::from(srs.to_vec()).points).unwrap();
+
+        // mock server answering every request with the SRS file
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut byte = [0u8];
+                while !request.ends_with(b"\r\n\r\n") {
+                    stream.read_exact(&mut byte).unwrap();
+                    request.push(byte[0]);
+                }
+                write!(
+                    stream,
+                    "HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let url = format!("http://{}/srs.json", address);
+        assert_eq!(
+            SerializedSRS::load_url(&url, Some(srs_digest(srs)))
+                .unwrap()
+                .to_ec_points(),
+            srs
+        );
+        assert!(SerializedSRS::load_url(&url, Some([0; 32])).is_err());
+        server.join().unwrap();
+
+        assert!(SerializedSRS::load_url("not a url", None).is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_load_async() {
+        let srs = SerializedSRS::from(small_srs().to_vec());
+        let gzip = TempPath::new("test_load_async.json.gz");
+        let gzip = gzip.as_str();
+
+        srs.dump(gzip).unwrap();
+        assert_eq!(
+            SerializedSRS::load_async(gzip)
+                .await
+                .unwrap()
+                .to_ec_points(),
+            SerializedSRS::load(gzip).unwrap().to_ec_points()
+        );
+        assert!(SerializedSRS::load_async("missing_srs.json").await.is_err());
+    }
+
+    #[test]
+    fn test_commitment_roundtrip() {
+        let srs = generate_srs(16, FrElement::from(SMALL_SRS_TAU));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let commitment = prover
+            .commit_lagrange(&random_poly(7), &lagrange_srs)
+            .unwrap();
+
+        let path = TempPath::new("test_commitment_roundtrip.json");
+        let path = path.as_str();
+        for point in [commitment, G1Point::neutral_element()] {
+            SerializedCommitment::from(&point).dump(path).unwrap();
+            let loaded = SerializedCommitment::load(path).unwrap();
+            assert_eq!(loaded.to_ec_point().unwrap(), point);
+        }
+    }
+
+    #[test]
+    fn test_commitment_key_bundle_roundtrip() {
+        use crate::srs::{generate_srs_g2, CommitmentKey};
+
+        let tau = FrElement::from(SMALL_SRS_TAU);
+        let key = CommitmentKey::new(small_srs().to_vec()).unwrap();
+        let path = TempPath::new("test_commitment_key_bundle_roundtrip.json");
+        let path = path.as_str();
+
+        for g2 in [None, Some(generate_srs_g2(&tau))] {
+            CommitmentKeyBundle::new(key.clone(), g2.clone())
+                .dump(path)
+                .unwrap();
+            let loaded = CommitmentKeyBundle::load(path).unwrap();
+
+            assert_eq!(loaded.key.powers_of_tau, key.powers_of_tau);
+            assert_eq!(loaded.key.lagrange_srs, key.lagrange_srs);
+            assert_eq!(loaded.g2, g2);
+        }
+
+        // a G2 SRS for another tau fails the pairing check
+        let other_g2 = generate_srs_g2(&(tau + FrElement::one()));
+        CommitmentKeyBundle::new(key, Some(other_g2))
+            .dump(path)
+            .unwrap();
+        assert!(CommitmentKeyBundle::load(path).is_err());
+    }
+
+    #[test]
+    fn test_reject_non_canonical_coordinates() {
+        let point = small_srs()[1].to_affine();
+        let canonical = SerializedSRS::from(vec![point.clone()]);
+
+        // x + p encodes the same field element with out-of-range limbs
+        let x = point.x().representative() + BLS12381FieldModulus::MODULUS;
+        let non_canonical = SerializedSRS {
+            points: vec![(x.to_string(), point.y().to_string())],
+        };
+
+        assert_eq!(
+            canonical.to_ec_points_checked(false, true).unwrap(),
+            vec![point.clone()]
+        );
+        assert_eq!(
+            non_canonical
+                .clone()
+                .to_ec_points_checked(false, false)
+                .unwrap(),
+            vec![point]
+        );
+        assert!(non_canonical.to_ec_points_checked(false, true).is_err());
+    }
 }
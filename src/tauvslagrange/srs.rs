@@ -1,37 +1,357 @@
+use std::{error::Error, fmt};
+
 use lambdaworks_math::{
     cyclic_group::IsGroup,
     elliptic_curve::{
-        short_weierstrass::curves::bls12_381::{curve::BLS12381Curve, default_types::FrElement},
+        short_weierstrass::curves::bls12_381::{
+            curve::BLS12381FieldElement, default_types::FrElement, twist::BLS12381TwistCurve,
+        },
         traits::IsEllipticCurve,
     },
 };
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    g1_generator,
+    prover::{MsmBackend, ProverError},
+    serialize::SrsWriter,
+    utils::{g1_to_uncompressed_bytes, scalar_mul, to_lagrange_basis, Basis},
+    G1Point, G2Point,
+};
 
-use crate::G1Point;
+#[derive(Debug)]
+pub enum SrsError {
+    NotPowerOfTwo(usize),
+}
+
+impl fmt::Display for SrsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SrsError::NotPowerOfTwo(len) => {
+                write!(f, "SRS length {} is not a power of two", len)
+            }
+        }
+    }
+}
+
+impl Error for SrsError {}
+
+/// Log2 of the SRS length, or an error if it is not a power of two (and so cannot be
+/// an FFT domain)
+pub fn assert_power_of_two(points: &[G1Point]) -> Result<u32, SrsError> {
+    if !points.len().is_power_of_two() {
+        return Err(SrsError::NotPowerOfTwo(points.len()));
+    }
+    Ok(points.len().trailing_zeros())
+}
 
 /// Generate SRS for a tau
 pub fn generate_srs(n: usize, tau: FrElement) -> Vec<G1Point> {
+    generate_srs_with_base(n, tau, g1_generator())
+}
+
+/// Generate SRS for a tau over another base point: `[base, tau * base, ..., tau^(n-1) * base]`,
+/// e.g. for a hash-to-curve generator. `base` must lie in the prime-order subgroup, or
+/// the scalars act modulo the wrong order and the commitments are meaningless
+pub fn generate_srs_with_base(n: usize, tau: FrElement, base: G1Point) -> Vec<G1Point> {
     // Generate powers of tau: tau^1, tau^2, ..., tau^n
     let powers_of_tau = vandemonde_challenge(&tau, n - 1);
 
-    let g1 = <BLS12381Curve as IsEllipticCurve>::generator();
-    let mut tau_g1 = vec![g1; n];
+    let mut tau_g1 = vec![base; n];
 
-    // Compute tau^i * g1 for i = 1, ..., n-1 in parallel
+    // Compute tau^i * base for i = 1, ..., n-1 in parallel
     tau_g1
         .par_iter_mut()
         .skip(1)
         .zip(&powers_of_tau)
         .for_each(|(g1, tau_i)| {
-            *g1 = g1.operate_with_self(tau_i.representative());
+            *g1 = scalar_mul(g1, tau_i);
         });
 
     tau_g1
 }
 
+/// [`generate_srs`] together with its [`srs_digest`]. Each point's uncompressed
+/// encoding (the costly affine normalization) is computed in the same parallel pass
+/// that produces it; the encodings are then hashed sequentially in index order, so the
+/// digest is exactly the one [`srs_digest`] gives
+pub fn generate_srs_with_digest(n: usize, tau: FrElement) -> (Vec<G1Point>, [u8; 32]) {
+    let powers_of_tau = vandemonde_challenge(&tau, n - 1);
+
+    let g1 = g1_generator();
+    let (tau_g1, encodings): (Vec<_>, Vec<_>) = std::iter::once(FrElement::one())
+        .chain(powers_of_tau)
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|tau_i| {
+            let point = scalar_mul(&g1, tau_i);
+            let bytes = g1_to_uncompressed_bytes(&point);
+            (point, bytes)
+        })
+        .unzip();
+
+    let digest = encodings
+        .iter()
+        .fold(Sha256::new(), |hasher, bytes| hasher.chain_update(bytes))
+        .finalize()
+        .into();
+
+    (tau_g1, digest)
+}
+
+/// Generate SRS for a tau with the powers in descending order:
+/// `[tau^(n-1) * G, ..., tau * G, G]`. This pairs with conventions that index
+/// coefficients from the leading one down; every commit function in this crate expects
+/// the ascending [`generate_srs`] order and gives wrong commitments with this one
+pub fn generate_srs_descending(n: usize, tau: FrElement) -> Vec<G1Point> {
+    let mut srs = generate_srs(n, tau);
+    srs.reverse();
+    srs
+}
+
+/// Number of points generated and written per batch by [`generate_and_dump`]
+const DUMP_CHUNK_SIZE: usize = 1 << 12;
+
+/// Generate the SRS of size `n` for `tau` straight into `file_path`, in the format
+/// [`crate::serialize::SerializedSRS::dump`] writes (gzip for `.gz` paths).
+/// Points are generated in parallel batches and written as they are produced, so only
+/// one batch of points and strings is in memory at a time instead of the whole SRS
+/// plus its serialized copy
+pub fn generate_and_dump(
+    n: usize,
+    tau: FrElement,
+    file_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    generate_and_dump_chunked(n, tau, file_path, DUMP_CHUNK_SIZE)
+}
+
+fn generate_and_dump_chunked(
+    n: usize,
+    tau: FrElement,
+    file_path: &str,
+    chunk_size: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let g1 = g1_generator();
+    let mut writer = SrsWriter::create(file_path)?;
+
+    let mut power = FrElement::one();
+    for start in (0..n).step_by(chunk_size) {
+        let powers = (start..n.min(start + chunk_size))
+            .map(|_| {
+                let current = power.clone();
+                power = &power * &tau;
+                current
+            })
+            .collect::<Vec<_>>();
+        let points = powers
+            .par_iter()
+            .map(|p| scalar_mul(&g1, p))
+            .collect::<Vec<_>>();
+
+        writer.write_points(&points)?;
+    }
+
+    writer.finish()
+}
+
+/// Generate SRS for a small integer tau, as used throughout the tests
+pub fn generate_srs_u64(n: usize, tau: u64) -> Vec<G1Point> {
+    generate_srs(n, FrElement::from(tau))
+}
+
+/// Approximate memory held by an SRS of `n` points: projective points are three
+/// 48-byte base field elements each, 144 bytes, ignoring the `Vec` header
+pub fn estimated_bytes(n: usize) -> usize {
+    n * std::mem::size_of::<G1Point>()
+}
+
+/// SRS length needed to commit a polynomial of `degree` evaluated with `blowup`.
+/// The prover evaluates on `(degree + 1).next_power_of_two() * blowup` points, and the
+/// product it commits can have that many coefficients, e.g. a degree n-1 polynomial
+/// (n a power of two) needs 2n points under blowup 2
+pub fn for_degree(degree: usize, blowup: usize) -> usize {
+    (degree + 1).next_power_of_two() * blowup
+}
+
+/// Prefix of the powers of tau for committing opening quotients. Dividing by
+/// (x - z) lowers the degree by one, so an SRS that commits polynomials of degree
+/// < n commits their quotients with its first n - 1 points
+pub fn quotient_srs(full_srs: &[G1Point]) -> &[G1Point] {
+    &full_srs[..full_srs.len().saturating_sub(1)]
+}
+
+/// SHA-256 over the 96-byte uncompressed encoding of every point, in order. Identifies
+/// an SRS independently of how its file was written (endianness, gzip)
+pub fn srs_digest(points: &[G1Point]) -> [u8; 32] {
+    points
+        .iter()
+        .fold(Sha256::new(), |hasher, point| {
+            hasher.chain_update(g1_to_uncompressed_bytes(point))
+        })
+        .finalize()
+        .into()
+}
+
+/// Generate the G2 part of the SRS for a tau: [G2, tau * G2]
+pub fn generate_srs_g2(tau: &FrElement) -> Vec<G2Point> {
+    let g2 = <BLS12381TwistCurve as IsEllipticCurve>::generator();
+    let tau_g2 = g2.operate_with_self(tau.representative());
+
+    vec![g2, tau_g2]
+}
+
+/// Generate a bivariate SRS `tau^i * sigma^j * G` for `i < n`, `j < m`, in row-major
+/// order: the point for `(i, j)` sits at index `i * m + j`
+pub fn generate_bivariate_srs(
+    n: usize,
+    m: usize,
+    tau: &FrElement,
+    sigma: &FrElement,
+) -> Vec<G1Point> {
+    let powers = |x: &FrElement, len: usize| {
+        std::iter::successors(Some(FrElement::one()), |p| Some(p * x))
+            .take(len)
+            .collect::<Vec<_>>()
+    };
+    let (tau_powers, sigma_powers) = (powers(tau, n), powers(sigma, m));
+
+    let g1 = g1_generator();
+    (0..n * m)
+        .into_par_iter()
+        .map(|k| {
+            let scalar = &tau_powers[k / m] * &sigma_powers[k % m];
+            scalar_mul(&g1, &scalar)
+        })
+        .collect()
+}
+
+/// Apply one participant's contribution to an existing SRS.
+/// Each power `srs[i]` is multiplied by `tau_contribution^i`, so an SRS for `tau`
+/// becomes an SRS for `tau * tau_contribution`
+pub fn combine_contributions(base: &[G1Point], tau_contribution: &FrElement) -> Vec<G1Point> {
+    let mut combined = base.to_vec();
+    if combined.len() < 2 {
+        return combined;
+    }
+
+    // Generate powers of the contribution: c^1, c^2, ..., c^(n-1)
+    let powers = vandemonde_challenge(tau_contribution, combined.len() - 1);
+
+    // Update srs[i] * c^i for i = 1, ..., n-1 in parallel
+    combined
+        .par_iter_mut()
+        .skip(1)
+        .zip(&powers)
+        .for_each(|(p, c_i)| {
+            *p = scalar_mul(p, c_i);
+        });
+
+    combined
+}
+
+/// Replay a setup ceremony: start from the SRS for tau = 1 (every point the generator)
+/// and apply each contribution in order with [`combine_contributions`]. The result is
+/// the SRS of size `n` for the product of all contributions
+pub fn from_transcript(contributions: &[FrElement], n: usize) -> Vec<G1Point> {
+    let g1 = g1_generator();
+
+    contributions.iter().fold(vec![g1; n], |srs, contribution| {
+        combine_contributions(&srs, contribution)
+    })
+}
+
+/// SRS points together with the basis they are in, so the commit functions can reject
+/// an SRS of the wrong basis instead of committing to garbage
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaggedSrs {
+    pub basis: Basis,
+    pub points: Vec<G1Point>,
+}
+
+impl TaggedSrs {
+    pub fn new(basis: Basis, points: Vec<G1Point>) -> Self {
+        TaggedSrs { basis, points }
+    }
+
+    /// The points, or [`ProverError::BasisMismatch`] if they are not in `expected`
+    pub fn points_in(&self, expected: Basis) -> Result<&[G1Point], ProverError> {
+        if self.basis != expected {
+            return Err(ProverError::BasisMismatch(expected, self.basis));
+        }
+        Ok(&self.points)
+    }
+}
+
+/// Powers of tau together with the Lagrange SRS derived from them, for committing
+/// evaluations over the subgroup of the same size
+#[derive(Clone, Debug)]
+pub struct CommitmentKey {
+    pub powers_of_tau: Vec<G1Point>,
+    pub lagrange_srs: Vec<G1Point>,
+}
+
+impl CommitmentKey {
+    pub fn new(powers_of_tau: Vec<G1Point>) -> Result<Self, ProverError> {
+        let lagrange_srs = to_lagrange_basis(&powers_of_tau)?;
+        Ok(CommitmentKey {
+            powers_of_tau,
+            lagrange_srs,
+        })
+    }
+
+    /// Commit to a polynomial given by its evaluations over the subgroup
+    pub fn commit_lagrange(&self, evaluations: &[FrElement]) -> Result<G1Point, ProverError> {
+        if evaluations.len() != self.lagrange_srs.len() {
+            return Err(ProverError::SRSLengthMismatch(
+                self.lagrange_srs.len(),
+                evaluations.len(),
+            ));
+        }
+
+        let scalars = evaluations
+            .par_iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+        MsmBackend::default().msm(&scalars, &self.lagrange_srs)
+    }
+
+    /// Prepare for a latency-sensitive first commit: start the rayon workers and read
+    /// every SRS point once, so a freshly loaded or swapped-out key is paged in.
+    /// The Pippenger MSM keeps no precomputed tables, so there is nothing else to
+    /// warm; this trims the first-request tail latency, not steady-state commits
+    pub fn warm(&self) {
+        let zero = BLS12381FieldElement::zero();
+        let at_infinity = self
+            .powers_of_tau
+            .par_iter()
+            .chain(self.lagrange_srs.par_iter())
+            .filter(|p| *p.z() == zero)
+            .count();
+        std::hint::black_box(at_infinity);
+    }
+}
+
+/// Replace `points[index]` with a different random point of the prime-order subgroup,
+/// so tests can check that SRS verification and commitment comparisons notice
+#[cfg(test)]
+pub fn corrupt_point(points: &mut [G1Point], index: usize) {
+    let g1 = g1_generator();
+    loop {
+        let replacement = scalar_mul(&g1, &crate::utils::random_fr());
+        if replacement != points[index] {
+            points[index] = replacement;
+            return;
+        }
+    }
+}
+
 /// Computes the powers of tau: tau^1, tau^2, ..., tau^n
 fn vandemonde_challenge(x: &FrElement, n: usize) -> Vec<FrElement> {
     let mut powers = Vec::with_capacity(n);
+    if n == 0 {
+        return powers;
+    }
     powers.push(x.clone());
     for i in 0..n - 1 {
         powers.push(x.pow(i as u64 + 2));
@@ -41,9 +361,140 @@ fn vandemonde_challenge(x: &FrElement, n: usize) -> Vec<FrElement> {
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        prover::{open, Prover},
+        test_fixtures::{small_srs, TempPath, SMALL_SRS_SIZE, SMALL_SRS_TAU},
+        utils::{aggregate_commitments, random_field_elements, random_poly},
+        verifier::{verify, OpeningItem},
+    };
 
     use super::*;
 
+    #[test]
+    fn test_srs_digest() {
+        let srs = small_srs();
+        assert_eq!(
+            srs_digest(srs),
+            srs_digest(&generate_srs(
+                SMALL_SRS_SIZE,
+                FrElement::from(SMALL_SRS_TAU)
+            ))
+        );
+        assert_ne!(srs_digest(srs), srs_digest(&srs[..SMALL_SRS_SIZE - 1]));
+        assert_ne!(
+            srs_digest(srs),
+            srs_digest(&generate_srs_descending(
+                SMALL_SRS_SIZE,
+                FrElement::from(SMALL_SRS_TAU)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_generate_srs_with_digest() {
+        let (srs, digest) =
+            generate_srs_with_digest(SMALL_SRS_SIZE, FrElement::from(SMALL_SRS_TAU));
+        assert_eq!(srs, small_srs());
+        assert_eq!(digest, srs_digest(&srs));
+    }
+
+    #[test]
+    fn test_generate_srs_matches_powers() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::utils::random_fr_with_rng;
+
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let g1 = g1_generator();
+        for n in [1, 2, 5, 16] {
+            let tau = random_fr_with_rng(&mut rng);
+            let srs = generate_srs(n, tau.clone());
+
+            assert_eq!(srs.len(), n);
+            for (i, point) in srs.iter().enumerate() {
+                assert_eq!(
+                    *point,
+                    g1.operate_with_self(tau.pow(i as u64).representative()),
+                    "n = {}, i = {}",
+                    n,
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupt_point() {
+        use crate::verifier::verify_srs_pair;
+
+        let g2 = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU));
+        let srs = generate_srs(16, FrElement::from(SMALL_SRS_TAU));
+        assert!(verify_srs_pair(&srs, &g2));
+
+        let mut corrupted = srs.clone();
+        corrupt_point(&mut corrupted, 1);
+        assert!(!verify_srs_pair(&corrupted, &g2));
+
+        // a point past the pair check still changes every commitment that uses it
+        let mut corrupted = srs.clone();
+        corrupt_point(&mut corrupted, 9);
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+        assert_ne!(
+            prover.commit_polynomial(&witness, &corrupted).unwrap(),
+            prover.commit_polynomial(&witness, &srs).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_assert_power_of_two() {
+        assert_eq!(assert_power_of_two(small_srs()).unwrap(), 3);
+        assert!(matches!(
+            assert_power_of_two(&small_srs()[..6]),
+            Err(SrsError::NotPowerOfTwo(6))
+        ));
+        assert!(assert_power_of_two(&[]).is_err());
+
+        // the SRS error reaches to_lagrange_basis callers intact
+        assert_eq!(
+            to_lagrange_basis(&small_srs()[..6])
+                .unwrap_err()
+                .to_string(),
+            ProverError::from(SrsError::NotPowerOfTwo(6)).to_string()
+        );
+    }
+
+    #[test]
+    fn test_generate_srs_with_base() {
+        let tau = FrElement::from(SMALL_SRS_TAU);
+        assert_eq!(
+            generate_srs_with_base(SMALL_SRS_SIZE, tau.clone(), g1_generator()),
+            small_srs()
+        );
+
+        // base 3G shifts every power by the same factor
+        let base = g1_generator().operate_with_self(3u64);
+        let srs = generate_srs_with_base(SMALL_SRS_SIZE, tau, base);
+        assert!(srs
+            .iter()
+            .zip(small_srs())
+            .all(|(p, q)| *p == q.operate_with_self(3u64)));
+    }
+
+    #[test]
+    fn test_generate_bivariate_srs() {
+        let (tau, sigma) = (FrElement::from(3), FrElement::from(5));
+        let srs = generate_bivariate_srs(2, 3, &tau, &sigma);
+
+        let g1 = g1_generator();
+        assert_eq!(srs.len(), 6);
+        assert_eq!(srs[0], g1);
+        // (i, j) = (1, 2) at 1 * 3 + 2: tau * sigma^2 = 75
+        assert_eq!(srs[5], g1.operate_with_self(75u64));
+        // the first row is the univariate SRS for sigma
+        assert_eq!(srs[..3], generate_srs(3, sigma));
+    }
+
     #[test]
     fn test_vandemonde_challenge() {
         let challenge = vandemonde_challenge(&FrElement::from(2), 5);
@@ -59,4 +510,138 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_combine_contributions() {
+        let a = FrElement::from(SMALL_SRS_TAU);
+        let b = FrElement::from(7);
+
+        let combined = combine_contributions(small_srs(), &b);
+
+        assert_eq!(combined, generate_srs(SMALL_SRS_SIZE, a * b));
+    }
+
+    #[test]
+    fn test_from_transcript() {
+        let (c0, c1) = (FrElement::from(6), FrElement::from(7));
+
+        assert_eq!(
+            from_transcript(&[c0.clone(), c1.clone()], SMALL_SRS_SIZE),
+            generate_srs(SMALL_SRS_SIZE, c0 * c1)
+        );
+        assert_eq!(from_transcript(&[], 2), generate_srs(2, FrElement::one()));
+    }
+
+    #[test]
+    fn test_quotient_srs() {
+        let srs = small_srs();
+        assert_eq!(quotient_srs(srs).len(), SMALL_SRS_SIZE - 1);
+        assert!(quotient_srs(&[]).is_empty());
+
+        // the quotient of a full-size polynomial commits with the prefix and verifies
+        let poly = random_poly(SMALL_SRS_SIZE - 1);
+        let point = FrElement::from(5);
+        let mut quotient = poly.clone();
+        quotient.ruffini_division_inplace(&point);
+        let (value, proof) = open(&poly, &point, srs).unwrap();
+        assert_eq!(
+            proof,
+            aggregate_commitments(quotient_srs(srs), quotient.coefficients()).unwrap()
+        );
+        let item = OpeningItem {
+            commitment: aggregate_commitments(&srs[..poly.coeff_len()], poly.coefficients())
+                .unwrap(),
+            point,
+            value,
+            proof,
+        };
+        assert!(verify(
+            &item,
+            &generate_srs_g2(&FrElement::from(SMALL_SRS_TAU))
+        ));
+    }
+
+    #[test]
+    fn test_commitment_key_warm() {
+        let key = CommitmentKey::new(small_srs().to_vec()).unwrap();
+        let evaluations = random_field_elements(SMALL_SRS_SIZE);
+
+        let cold = key.commit_lagrange(&evaluations).unwrap();
+        key.warm();
+        assert_eq!(key.commit_lagrange(&evaluations).unwrap(), cold);
+        assert!(key.commit_lagrange(&evaluations[1..]).is_err());
+    }
+
+    #[test]
+    fn test_generate_srs_descending() {
+        let mut descending =
+            generate_srs_descending(SMALL_SRS_SIZE, FrElement::from(SMALL_SRS_TAU));
+        assert_eq!(descending.last().unwrap(), &g1_generator());
+
+        descending.reverse();
+        assert_eq!(descending, small_srs());
+    }
+
+    #[test]
+    fn test_generate_srs_u64() {
+        assert_eq!(
+            generate_srs_u64(8, 42),
+            generate_srs(8, FrElement::from(42))
+        );
+    }
+
+    #[test]
+    fn test_estimated_bytes() {
+        assert_eq!(estimated_bytes(1), 144);
+        let estimate = estimated_bytes(1 << 20);
+        assert!((140 << 20..=150 << 20).contains(&estimate));
+    }
+
+    #[test]
+    fn test_generate_and_dump() {
+        use crate::serialize::SerializedSRS;
+
+        let tau = FrElement::from(SMALL_SRS_TAU);
+        for extension in ["json", "json.gz"] {
+            let streamed = TempPath::new(&format!("test_generate_and_dump_streamed.{}", extension));
+            let three_pass =
+                TempPath::new(&format!("test_generate_and_dump_three_pass.{}", extension));
+            let (streamed, three_pass) = (streamed.as_str(), three_pass.as_str());
+
+            // several batches, the last one partial
+            generate_and_dump_chunked(10, tau.clone(), streamed, 4).unwrap();
+            SerializedSRS::from(generate_srs(10, tau.clone()))
+                .dump(three_pass)
+                .unwrap();
+
+            assert_eq!(
+                SerializedSRS::load(streamed).unwrap().to_ec_points(),
+                generate_srs(10, tau.clone())
+            );
+            if extension == "json" {
+                assert_eq!(
+                    std::fs::read(streamed).unwrap(),
+                    std::fs::read(three_pass).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_for_degree() {
+        let n = 8;
+        assert_eq!(for_degree(n - 1, 2), 2 * n);
+        assert_eq!(for_degree(n, 2), 4 * n);
+        assert_eq!(for_degree(3, 4), 16);
+
+        // committing a degree n-1 polynomial needs exactly 2n powers of tau
+        let srs = generate_srs(for_degree(n - 1, 2), FrElement::from(SMALL_SRS_TAU));
+        let prover = Prover::new(random_poly(n - 1)).unwrap();
+        let witness = random_poly(n - 1);
+
+        assert!(prover.commit_polynomial(&witness, &srs).is_ok());
+        assert!(prover
+            .commit_polynomial(&witness, &srs[..2 * n - 1])
+            .is_err());
+    }
 }
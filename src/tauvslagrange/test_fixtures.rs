@@ -0,0 +1,41 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::default_types::FrElement;
+
+use crate::{srs::generate_srs, G1Point};
+
+/// Tau used for the shared test SRS
+pub const SMALL_SRS_TAU: u64 = 42;
+/// Number of points in the shared test SRS
+pub const SMALL_SRS_SIZE: usize = 8;
+
+/// Fixed SRS (tau = 42, n = 8) shared across module tests, generated once
+pub fn small_srs() -> &'static [G1Point] {
+    static SRS: OnceLock<Vec<G1Point>> = OnceLock::new();
+    SRS.get_or_init(|| generate_srs(SMALL_SRS_SIZE, FrElement::from(SMALL_SRS_TAU)))
+}
+
+/// File under the temp dir, unique to the process and to `name`, e.g. the test name plus
+/// an extension, so parallel tests and concurrent runs don't share files. The file is
+/// removed on drop, also when the test fails
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    pub fn new(name: &str) -> Self {
+        TempPath(std::env::temp_dir().join(format!(
+            "tauvslagrange_{}_{}",
+            std::process::id(),
+            name
+        )))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
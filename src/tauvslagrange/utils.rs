@@ -2,46 +2,157 @@ use lambdaworks_math::{
     cyclic_group::IsGroup,
     elliptic_curve::{
         short_weierstrass::curves::bls12_381::{
-            curve::BLS12381Curve,
-            default_types::{FrElement, FrField},
+            compression::{check_point_is_in_subgroup, compress_g1_point},
+            curve::BLS12381FieldElement,
+            default_types::{FrConfig, FrElement, FrField},
+            field_extension::BLS12381FieldModulus,
+            pairing::BLS12381AtePairing,
         },
-        traits::IsEllipticCurve,
+        traits::{FromAffine, IsPairing},
     },
-    fft::{
-        cpu::{bit_reversing::in_place_bit_reverse_permute, roots_of_unity},
-        errors::FFTError,
+    errors::ByteConversionError,
+    fft::{cpu::bit_reversing::in_place_bit_reverse_permute, errors::FFTError},
+    field::{
+        element::FieldElement,
+        fields::montgomery_backed_prime_fields::IsModulus,
+        traits::{IsFFTField, RootsConfig},
     },
-    field::traits::{IsPrimeField, RootsConfig},
+    msm::naive::msm,
     polynomial::Polynomial,
-    unsigned_integer::element::U256,
+    traits::ByteConversion,
+    unsigned_integer::element::{U256, U384},
 };
 use rand::Rng;
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 
-use crate::G1Point;
+use crate::{
+    domain::Domain,
+    g1_generator,
+    prover::{sum_points, Prover, ProverError},
+    srs::assert_power_of_two,
+    G1Point, G2Point,
+};
+
+/// `scalar * point`, through the scalar's integer representative. Every G1 scalar
+/// multiplication by a field element in the crate goes through here
+pub fn scalar_mul(point: &G1Point, scalar: &FrElement) -> G1Point {
+    point.operate_with_self(scalar.representative())
+}
 
-/// Generate a random field element
+/// Generate a random field element.
+/// Reducing 256 uniformly random bits mod r would favour small values (r is well
+/// below 2^256), so the top bit is masked off (r > 2^254) and out-of-range draws are
+/// rejected, which keeps the result uniform
 pub fn random_fr() -> FrElement {
-    let mut rng = rand::thread_rng();
-    FrElement::new(U256 {
-        limbs: [
-            rng.gen::<u64>(),
-            rng.gen::<u64>(),
-            rng.gen::<u64>(),
-            rng.gen::<u64>(),
-        ],
-    })
+    random_fr_with_rng(&mut rand::thread_rng())
+}
+
+/// [`random_fr`] drawing from the given RNG, e.g. a seeded one for reproducible tests
+pub fn random_fr_with_rng(rng: &mut impl Rng) -> FrElement {
+    loop {
+        let value = U256 {
+            limbs: [
+                rng.gen::<u64>() >> 1,
+                rng.gen::<u64>(),
+                rng.gen::<u64>(),
+                rng.gen::<u64>(),
+            ],
+        };
+        if is_reduced(&value) {
+            return FrElement::new(value);
+        }
+    }
+}
+
+/// Check that `value` is a canonical (fully reduced) encoding of a scalar, i.e. `value < r`.
+/// `FrElement`s are always reduced once constructed, so this applies to the raw limbs
+/// before conversion
+pub fn is_reduced(value: &U256) -> bool {
+    *value < FrConfig::MODULUS
+}
+
+/// Size `2^domain_order` of an FFT domain. Panics if the order exceeds the 2-adicity of
+/// Fr, since no such domain exists (and the shift would overflow from 64 on)
+fn domain_size_for_order(domain_order: u32) -> u64 {
+    assert!(
+        domain_order <= max_fft_domain_log2(),
+        "domain order {} exceeds the 2-adicity {} of Fr",
+        domain_order,
+        max_fft_domain_log2()
+    );
+    1_u64 << domain_order
+}
+
+/// Generate a random field element outside the domain of size `2^domain_order`,
+/// i.e. one that is not an nth root of unity. Opening at such a point keeps the
+/// quotient well defined. Panics if `domain_order` exceeds [`max_fft_domain_log2`]
+pub fn random_non_domain_point(domain_order: u32) -> FrElement {
+    let domain_size = domain_size_for_order(domain_order);
+    loop {
+        let point = random_fr();
+        if point.pow(domain_size) != FrElement::one() {
+            return point;
+        }
+    }
+}
+
+/// Fiat-Shamir challenge for a commitment: SHA-256 of `seed`, the compressed commitment
+/// and a counter, read as a big-endian scalar with the top bit masked. The counter is
+/// bumped until the result is canonical and outside the domain of size `2^domain_order`,
+/// so the same seed and commitment always give the same point. Panics if `domain_order`
+/// exceeds [`max_fft_domain_log2`]
+pub fn fiat_shamir_challenge(seed: &[u8], commitment: &G1Point, domain_order: u32) -> FrElement {
+    let compressed = compress_g1_point(commitment);
+    let domain_size = domain_size_for_order(domain_order);
+
+    (0u64..)
+        .find_map(|counter| {
+            let digest = Sha256::new()
+                .chain_update(seed)
+                .chain_update(&compressed)
+                .chain_update(counter.to_be_bytes())
+                .finalize();
+            let mut bytes: [u8; 32] = digest.into();
+            bytes[0] &= 0x7f;
+
+            let value = U256::from_bytes_be(&bytes).ok()?;
+            let point = is_reduced(&value).then(|| FrElement::new(value))?;
+            (point.pow(domain_size) != FrElement::one()).then_some(point)
+        })
+        .expect("a counter eventually yields a non-domain scalar")
+}
+
+/// Challenge combining the chunk commitments of [`Prover::commit_chunked`]: the
+/// [`fiat_shamir_challenge`] seeded with every compressed chunk commitment in order,
+/// over their sum
+pub fn chunked_challenge(chunk_commitments: &[G1Point]) -> FrElement {
+    let seed = chunk_commitments
+        .iter()
+        .flat_map(compress_g1_point)
+        .collect::<Vec<_>>();
+    fiat_shamir_challenge(&seed, &sum_points(chunk_commitments), 0)
 }
 
 /// Generate `n` random field elements
 pub fn random_field_elements(n: usize) -> Vec<FrElement> {
     let mut result = vec![FrElement::zero(); n];
+    fill_random_field_elements(&mut result);
+    result
+}
+
+/// Generate `n` random field elements from the given RNG. Sequential, unlike
+/// [`random_field_elements`], which draws from a thread-local RNG per worker
+pub fn random_field_elements_with_rng(rng: &mut impl Rng, n: usize) -> Vec<FrElement> {
+    (0..n).map(|_| random_fr_with_rng(rng)).collect()
+}
 
-    result.par_iter_mut().for_each(|op| {
+/// Overwrite every element of `buf` with a fresh random field element, in parallel,
+/// reusing the allocation across repeated witness generation
+pub fn fill_random_field_elements(buf: &mut [FrElement]) {
+    buf.par_iter_mut().for_each(|op| {
         *op = random_fr();
     });
-
-    result
 }
 
 /// Generate a polynomial of degree `degree` with random coefficients
@@ -50,6 +161,111 @@ pub fn random_poly(degree: usize) -> Polynomial<FrElement> {
     Polynomial::new(&random_field_elements(degree + 1))
 }
 
+/// [`random_poly`] drawing its coefficients from the given RNG
+pub fn random_poly_with_rng(rng: &mut impl Rng, degree: usize) -> Polynomial<FrElement> {
+    Polynomial::new(&random_field_elements_with_rng(rng, degree + 1))
+}
+
+/// Generate a polynomial of degree at most `degree` with coefficients drawn uniformly
+/// from `[0, bound)`, e.g. bit vectors with `bound = 2`. Panics if `bound` is zero
+pub fn random_poly_bounded(degree: usize, bound: u64) -> Polynomial<FrElement> {
+    let mut rng = rand::thread_rng();
+    let coefficients = (0..=degree)
+        .map(|_| FrElement::from(rng.gen_range(0..bound)))
+        .collect::<Vec<_>>();
+    Polynomial::new(&coefficients)
+}
+
+/// The monomial `x^degree` as a witness, whose commitment over the powers of tau is
+/// exactly `srs[degree]`. Panics if `degree` does not fit a domain of `domain_len`
+pub fn monomial(degree: usize, domain_len: usize) -> Polynomial<FrElement> {
+    assert!(
+        degree < domain_len,
+        "x^{} does not fit a domain of {}",
+        degree,
+        domain_len
+    );
+    let mut coefficients = vec![FrElement::zero(); degree + 1];
+    coefficients[degree] = FrElement::one();
+    Polynomial::new(&coefficients)
+}
+
+/// Check that no field element appears twice. Sorts the canonical representatives,
+/// so it stays O(n log n) for large evaluation sets
+pub fn all_distinct(points: &[FrElement]) -> bool {
+    let mut representatives = points
+        .iter()
+        .map(|p| p.representative())
+        .collect::<Vec<_>>();
+    representatives.sort_unstable();
+    representatives.windows(2).all(|w| w[0] != w[1])
+}
+
+/// Basis an SRS is expressed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Basis {
+    /// Powers of tau: tau^i * G
+    Monomial,
+    /// Lagrange basis polynomials at tau: L_i(tau) * G
+    Lagrange,
+    /// Neither relation holds
+    Unknown,
+}
+
+/// Detect which basis an SRS is in, given the G2 SRS `[G2, tau * G2]`.
+/// A monomial SRS satisfies `e(srs[1], g2[0]) == e(srs[0], g2[1])`, since srs[1] = tau * srs[0].
+/// A Lagrange SRS doesn't, but its points sum to the generator (the L_i sum to 1).
+/// Only the first pair is checked against the pairing
+pub fn detect_basis(points: &[G1Point], g2: &[G2Point]) -> Basis {
+    if points.len() < 2 || g2.len() < 2 {
+        return Basis::Unknown;
+    }
+
+    let pairing =
+        BLS12381AtePairing::compute_batch(&[(&points[1], &g2[0]), (&points[0].neg(), &g2[1])]);
+    if pairing == FieldElement::one() {
+        return Basis::Monomial;
+    }
+
+    let sum = points
+        .par_iter()
+        .cloned()
+        .reduce(G1Point::neutral_element, |a, b| a.operate_with(&b));
+    if sum == g1_generator() {
+        return Basis::Lagrange;
+    }
+
+    Basis::Unknown
+}
+
+/// Multiply two polynomials given in evaluation form over the same domain.
+/// Chain calls to multiply more polynomials, as long as the domain is large enough
+/// for the degree of the product
+pub fn mul_evaluations(a: &[FrElement], b: &[FrElement]) -> Result<Vec<FrElement>, ProverError> {
+    check_evaluation_lengths(a, b)?;
+
+    Ok(a.par_iter().zip(b).map(|(a, b)| a * b).collect())
+}
+
+/// Subtract two polynomials in evaluation form, point by point: `a - b`.
+/// Unlike the product, the difference never outgrows the domain
+pub fn sub_evaluations(a: &[FrElement], b: &[FrElement]) -> Result<Vec<FrElement>, ProverError> {
+    check_evaluation_lengths(a, b)?;
+
+    Ok(a.par_iter().zip(b).map(|(a, b)| a - b).collect())
+}
+
+fn check_evaluation_lengths(a: &[FrElement], b: &[FrElement]) -> Result<(), ProverError> {
+    if a.len() != b.len() {
+        return Err(ProverError::InvalidFFTOperation(format!(
+            "Evaluation lengths do not match: {} != {}",
+            a.len(),
+            b.len()
+        )));
+    }
+    Ok(())
+}
+
 /// Fast Fourier transformation for elliptic curve BLS12-381 G1 points using the domain
 pub fn fft_g(points: &[G1Point], domain: &[FrElement]) -> Vec<G1Point> {
     if points.len() == 1 {
@@ -68,7 +284,7 @@ pub fn fft_g(points: &[G1Point], domain: &[FrElement]) -> Vec<G1Point> {
     let odd_fft = fft_g(&odd_points, &sub_domain);
     let even_fft = fft_g(&even_points, &sub_domain);
 
-    let g1 = <BLS12381Curve as IsEllipticCurve>::generator();
+    let g1 = g1_generator();
     let mut result = vec![g1; points.len()];
 
     odd_fft
@@ -77,7 +293,7 @@ pub fn fft_g(points: &[G1Point], domain: &[FrElement]) -> Vec<G1Point> {
         .zip(&even_fft)
         .enumerate()
         .for_each(|(i, (odd, even))| {
-            let even_times_root = even.operate_with_self(domain[i].representative());
+            let even_times_root = scalar_mul(even, &domain[i]);
 
             result[i] = odd.operate_with(&even_times_root);
             result[i + odd_fft.len()] = odd.operate_with(&even_times_root.neg());
@@ -86,7 +302,30 @@ pub fn fft_g(points: &[G1Point], domain: &[FrElement]) -> Vec<G1Point> {
     result
 }
 
-/// Fast Fourier transformation for elliptic curve BLS12-381 G1 points using the domain(twiddle factors)
+/// [`fft_g`] over a [`Domain`]: the points are read as the coefficients of a polynomial
+/// and output `i` is its evaluation at `offset * w^i`, handling a coset by prescaling the
+/// j-th point by `offset^j`. The number of points must match the domain's evaluation size
+pub fn fft_g_with_domain(points: &[G1Point], domain: &Domain) -> Result<Vec<G1Point>, ProverError> {
+    if points.len() != domain.size() {
+        return Err(FFTError::InputError(points.len()).into());
+    }
+
+    let generator = domain.generator();
+    let roots = std::iter::successors(Some(FrElement::one()), |w| Some(w * &generator))
+        .take(domain.size())
+        .collect::<Vec<_>>();
+
+    match domain.offset() {
+        Some(offset) => {
+            let offset_inv = offset.inv().map_err(FFTError::from)?;
+            Ok(fft_g(&coset_prescale(points, &offset_inv), &roots))
+        }
+        None => Ok(fft_g(points, &roots)),
+    }
+}
+
+/// Fast Fourier transformation for elliptic curve BLS12-381 G1 points using the domain(twiddle factors),
+/// e.g. from [`Domain::twiddles`]
 pub fn in_place_nr_2radix_fft_g(input: &mut [G1Point], twiddles: &[FrElement]) {
     // divide input in groups, starting with 1, duplicating the number of groups in each stage.
     let mut group_count = 1;
@@ -106,9 +345,9 @@ pub fn in_place_nr_2radix_fft_g(input: &mut [G1Point], twiddles: &[FrElement]) {
             let w = &twiddles[group]; // a twiddle factor is used per group
 
             for i in first_in_group..first_in_next_group {
-                let wi = &input[i + group_size / 2].operate_with_self(w.representative());
+                let wi = &scalar_mul(&input[i + group_size / 2], w);
 
-                let y0 = &input[i].operate_with(&wi);
+                let y0 = &input[i].operate_with(wi);
                 let y1 = &input[i].operate_with(&wi.neg());
 
                 input[i] = y0.clone();
@@ -120,40 +359,873 @@ pub fn in_place_nr_2radix_fft_g(input: &mut [G1Point], twiddles: &[FrElement]) {
     }
 }
 
+/// Polynomial whose evaluations over the subgroup of size `2^domain_log2` are given by
+/// `(index, value)` pairs, with every missing index taken as zero, e.g. to see what a
+/// sparse Lagrange commitment committed to. Out-of-range indices are rejected and a
+/// repeated index keeps its last value
+pub fn interpolate_from_domain_evals(
+    pairs: &[(usize, FrElement)],
+    domain_log2: u32,
+) -> Result<Polynomial<FrElement>, FFTError> {
+    let domain =
+        Domain::new(domain_log2, 1).map_err(|_| FFTError::OrderError(domain_log2.into()))?;
+
+    let mut evaluations = vec![FrElement::zero(); domain.size()];
+    for (index, value) in pairs {
+        *evaluations
+            .get_mut(*index)
+            .ok_or(FFTError::InputError(*index))? = value.clone();
+    }
+
+    domain.interpolate(&evaluations)
+}
+
+/// Largest supported FFT domain (log2), bounded by the 2-adicity of the scalar field
+pub fn max_fft_domain_log2() -> u32 {
+    FrField::TWO_ADICITY as u32
+}
+
 /// Inverse Fast Fourier transformation for elliptic curve BLS12-381 G1 points using the domain(twiddle factors)
-pub fn to_lagrange_basis(points: &[G1Point]) -> Result<Vec<G1Point>, FFTError> {
-    let order = points.len().trailing_zeros();
-    let twiddles = roots_of_unity::get_twiddles(order.into(), RootsConfig::BitReverseInversed)?;
+///
+/// Ordering contract: output `i` is `L_i(tau) * G` for the Lagrange polynomial that is 1
+/// at `w^i`, with `w` the primitive root lambdaworks picks for the domain size, i.e.
+/// natural order. The bit-reversed inverse twiddles plus the final bit-reverse permute
+/// produce exactly that, matching `evaluate_fft` (and so the prover), whose output `i`
+/// is the evaluation at `w^i`. Changing either side breaks every Lagrange commitment
+pub fn to_lagrange_basis(points: &[G1Point]) -> Result<Vec<G1Point>, ProverError> {
+    to_lagrange_basis_with_permutation(points, in_place_bit_reverse_permute)
+}
 
-    let mut results = points.to_vec();
-    in_place_nr_2radix_fft_g(&mut results, &twiddles);
-    in_place_bit_reverse_permute(&mut results);
+/// [`to_lagrange_basis`] with the output permutation as a parameter, so the `validate`
+/// check can be exercised against a broken one
+fn to_lagrange_basis_with_permutation(
+    points: &[G1Point],
+    permute: fn(&mut [G1Point]),
+) -> Result<Vec<G1Point>, ProverError> {
+    let (twiddles, inv_length) = lagrange_twiddles(points)?;
+    let results = lagrange_transform(points, &twiddles, &inv_length, permute);
+
+    #[cfg(feature = "validate")]
+    validate_lagrange_roundtrip(points, &results)?;
 
-    let mut exp = FrField::modulus_minus_one();
-    exp.limbs[exp.limbs.len() - 1] -= 1;
+    Ok(results)
+}
 
+/// Inverse FFT twiddles and 1/n for a transform of the points. Every Lagrange transform
+/// gets its twiddles here, so this is where a length that is not a power of two is
+/// rejected
+fn lagrange_twiddles(points: &[G1Point]) -> Result<(Vec<FrElement>, FrElement), ProverError> {
+    let domain = Domain::new(assert_power_of_two(points)?, 1)?;
+    let twiddles = domain.twiddles(RootsConfig::BitReverseInversed)?;
     let inv_length = FrElement::from(points.len() as u64)
-        .pow(exp)
-        .representative();
+        .inv()
+        .map_err(FFTError::from)?;
+
+    Ok((twiddles, inv_length))
+}
+
+fn lagrange_transform(
+    points: &[G1Point],
+    twiddles: &[FrElement],
+    inv_length: &FrElement,
+    permute: fn(&mut [G1Point]),
+) -> Vec<G1Point> {
+    let mut results = points.to_vec();
+    in_place_nr_2radix_fft_g(&mut results, twiddles);
+    permute(&mut results);
 
     results.par_iter_mut().for_each(|p| {
-        *p = p.operate_with_self(inv_length);
+        *p = scalar_mul(p, inv_length);
     });
 
-    Ok(results)
+    results
+}
+
+/// Run the forward transform on a Lagrange SRS, tau^i * G = sum_j w^(ij) * L_j(tau) * G,
+/// and assert it recovers the powers of tau. Catches twiddle-ordering and
+/// bit-reversal regressions at the cost of a second, slower G1 FFT
+#[cfg(feature = "validate")]
+fn validate_lagrange_roundtrip(
+    points: &[G1Point],
+    lagrange: &[G1Point],
+) -> Result<(), ProverError> {
+    let domain = Domain::new(points.len().trailing_zeros(), 1)?;
+
+    assert!(
+        fft_g_with_domain(lagrange, &domain)? == points,
+        "to_lagrange_basis roundtrip does not recover the input"
+    );
+    Ok(())
+}
+
+/// Lagrange basis over the negacyclic domain `psi * w^j`, j = 0..n, for BLS12-381 G1 points.
+///
+/// Here `psi` is a primitive 2n-th root of unity and `w = psi^2` generates the usual
+/// n-th roots of unity, so the domain is the set of odd powers of `psi`. Writing the
+/// evaluations as `p(psi * w^j) = sum_i (c_i * psi^i) * w^(ij)`, the coefficients are
+/// `c_i = psi^-i * (1/n) * sum_j p(psi * w^j) * w^-ij`, hence
+///
+/// `L_j(tau) * G = (1/n) * sum_i w^-ij * (psi^-i * tau^i * G)`
+///
+/// i.e. the SRS is prescaled by `psi^-i` and then run through [`to_lagrange_basis`].
+/// Evaluations to commit against the result are in natural order, `p(psi * w^j)`.
+pub fn to_lagrange_basis_negacyclic(points: &[G1Point]) -> Result<Vec<G1Point>, ProverError> {
+    let psi = Domain::new(points.len().trailing_zeros() + 1, 1)?.generator();
+
+    to_lagrange_basis(&coset_prescale(points, &psi))
+}
+
+/// Lagrange basis for the points over the given domain. For a coset `g * <w>` the
+/// coefficients satisfy `c_i = g^-i * c'_i`, where `c'` interpolates the same evaluations
+/// over `<w>`, so the SRS is prescaled by `g^-i` before the inverse FFT.
+/// The SRS length must match the domain's evaluation size
+pub fn to_lagrange_basis_with_domain(
+    points: &[G1Point],
+    domain: &Domain,
+) -> Result<Vec<G1Point>, ProverError> {
+    if points.len() != domain.size() {
+        return Err(FFTError::InputError(points.len()).into());
+    }
+
+    match domain.offset() {
+        Some(offset) => to_lagrange_basis(&coset_prescale(points, offset)),
+        None => to_lagrange_basis(points),
+    }
+}
+
+/// Lagrange SRS for the smaller domain of size `2^target_log2`, derived from the
+/// monomial SRS it came from. A Lagrange SRS can't just be truncated: L_i for the
+/// size 2n domain is a different polynomial than L_i for the size n domain (each
+/// mixes every power of tau up to its domain size), so the powers of tau are
+/// truncated instead and transformed again
+pub fn lagrange_srs_downsample(
+    monomial_srs: &[G1Point],
+    target_log2: u32,
+) -> Result<Vec<G1Point>, ProverError> {
+    let size = 1usize
+        .checked_shl(target_log2)
+        .filter(|size| *size <= monomial_srs.len())
+        .ok_or(FFTError::InputError(monomial_srs.len()))?;
+
+    to_lagrange_basis(&monomial_srs[..size])
+}
+
+/// Lagrange SRS for each coset `offset * <w>` of the subgroup of size `points.len()`,
+/// one per offset, e.g. a chain `g, g * eta, g * eta^2, ...` for FRI-style folding.
+/// The twiddles are computed once for the whole chain. Memory grows with the chain:
+/// the result holds `offsets.len()` full SRS copies. Zero offsets are rejected
+pub fn to_lagrange_basis_coset_chain(
+    points: &[G1Point],
+    offsets: &[FrElement],
+) -> Result<Vec<Vec<G1Point>>, ProverError> {
+    if offsets.iter().any(|o| *o == FrElement::zero()) {
+        return Err(FFTError::InputError(points.len()).into());
+    }
+    let (twiddles, inv_length) = lagrange_twiddles(points)?;
+
+    Ok(offsets
+        .iter()
+        .map(|offset| {
+            lagrange_transform(
+                &coset_prescale(points, offset),
+                &twiddles,
+                &inv_length,
+                in_place_bit_reverse_permute,
+            )
+        })
+        .collect())
+}
+
+/// Scale the i-th point by `offset^-i`
+fn coset_prescale(points: &[G1Point], offset: &FrElement) -> Vec<G1Point> {
+    let offset_inv = offset.inv().unwrap();
+
+    let mut prescaled = points.to_vec();
+    prescaled.par_iter_mut().enumerate().for_each(|(i, p)| {
+        *p = scalar_mul(p, &offset_inv.pow(i as u64));
+    });
+
+    prescaled
+}
+
+/// Sum of all SRS points. Over a Lagrange SRS this commits the all-ones witness (and
+/// over the powers of tau, the polynomial with all coefficients one), so it doubles as
+/// a cheap check that an SRS loaded as expected
+pub fn srs_sum(points: &[G1Point]) -> G1Point {
+    sum_points(points)
+}
+
+/// Commitment to the i-th Lagrange basis polynomial L_i, which is just `lagrange_srs[i]`.
+/// Panics if `i` is out of range
+pub fn lagrange_commitment(lagrange_srs: &[G1Point], i: usize) -> G1Point {
+    lagrange_srs[i].clone()
+}
+
+/// Check that `lagrange_commitment(lagrange_srs, i)` equals committing the unit vector e_i
+/// through [`Prover::commit_lagrange`], i.e. the witness L_i that is 1 at the i-th domain
+/// point and 0 elsewhere. The prover polynomial is the constant 1 on the SRS domain
+/// without blowup, so the commitment only depends on the witness evaluations
+pub fn verify_lagrange_commitment(lagrange_srs: &[G1Point], i: usize) -> Result<bool, ProverError> {
+    let domain = Domain::for_coeff_len(lagrange_srs.len(), 1)?;
+    let prover = Prover::new_with_domain(Polynomial::new(&[FrElement::one()]), &domain)?;
+
+    let unit = (0..lagrange_srs.len())
+        .map(|j| {
+            if j == i {
+                FrElement::one()
+            } else {
+                FrElement::zero()
+            }
+        })
+        .collect::<Vec<_>>();
+    let witness = domain.interpolate(&unit)?;
+    let commitment = prover.commit_lagrange(&witness, lagrange_srs)?;
+
+    Ok(commitment == lagrange_commitment(lagrange_srs, i))
+}
+
+/// Check that committing `witness` through the Lagrange SRS and through the powers
+/// of tau gives the same point, comparing exact affine coordinates (both sides at
+/// infinity also counts as equal). This is the equality `main.rs` prints side by side
+pub fn relate_commitments(
+    prover: &Prover,
+    witness: &Polynomial<FrElement>,
+    pwrs_tau: &[G1Point],
+    lagrange_srs: &[G1Point],
+) -> Result<bool, ProverError> {
+    let lagrange = prover.commit_lagrange(witness, lagrange_srs)?;
+    let monomial = prover.commit_polynomial(witness, pwrs_tau)?;
+
+    let affine = |p: &G1Point| {
+        (!p.is_neutral_element()).then(|| {
+            let p = p.to_affine();
+            (p.x().clone(), p.y().clone())
+        })
+    };
+    Ok(affine(&lagrange) == affine(&monomial))
+}
+
+/// Random linear combination of commitments, `sum_i scalars[i] * commitments[i]`.
+/// The lengths must match; no commitments give the identity
+pub fn aggregate_commitments(
+    commitments: &[G1Point],
+    scalars: &[FrElement],
+) -> Result<G1Point, ProverError> {
+    let scalars = scalars
+        .iter()
+        .map(|s| s.representative())
+        .collect::<Vec<_>>();
+    Ok(msm(&scalars, commitments)?)
+}
+
+/// Commitment to `p(x) + constant` given the commitment `C` to `p`: the constant term
+/// commits against `srs[0] = G`, so this is `C + constant * srs0` without recommitting
+pub fn add_constant_to_commitment(
+    commitment: &G1Point,
+    constant: &FrElement,
+    srs0: &G1Point,
+) -> G1Point {
+    commitment.operate_with(&scalar_mul(srs0, constant))
+}
+
+/// Commitment to `k * p(x)` given the commitment `C` to `p`: commitments are linear,
+/// so this is `k * C` without recommitting
+pub fn scale_commitment(commitment: &G1Point, k: &FrElement) -> G1Point {
+    scalar_mul(commitment, k)
+}
+
+/// Base-10 digits of `value`, without leading zeros ("0" for zero)
+pub fn to_decimal_string(value: &U384) -> String {
+    // peel off 19 decimal digits at a time, the largest power of ten below 2^64
+    const CHUNK: u64 = 10_000_000_000_000_000_000;
+    let mut limbs = value.limbs;
+    let mut chunks = Vec::new();
+    while limbs.iter().any(|&limb| limb != 0) {
+        // limbs are most significant first
+        let mut rem = 0u128;
+        for limb in limbs.iter_mut() {
+            let acc = (rem << 64) | *limb as u128;
+            *limb = (acc / CHUNK as u128) as u64;
+            rem = acc % CHUNK as u128;
+        }
+        chunks.push(rem as u64);
+    }
+
+    let Some((most_significant, rest)) = chunks.split_last() else {
+        return "0".to_string();
+    };
+    rest.iter()
+        .rev()
+        .fold(most_significant.to_string(), |mut digits, chunk| {
+            digits.push_str(&format!("{:019}", chunk));
+            digits
+        })
+}
+
+/// Encode a point in the 96-byte uncompressed form of the IETF / ZCash BLS12-381
+/// serialization: big-endian x || y, with the infinity flag (0x40 in the first byte)
+/// set and all other bytes zero for the neutral element
+pub fn g1_to_uncompressed_bytes(point: &G1Point) -> [u8; 96] {
+    let mut bytes = [0u8; 96];
+    if *point == G1Point::neutral_element() {
+        bytes[0] = 1 << 6;
+        return bytes;
+    }
+
+    let affine = point.to_affine();
+    bytes[..48].copy_from_slice(&affine.x().to_bytes_be());
+    bytes[48..].copy_from_slice(&affine.y().to_bytes_be());
+    bytes
+}
+
+/// Inverse of [`g1_to_uncompressed_bytes`]. Rejects compressed or malformed flags,
+/// non-canonical coordinates and points off the curve or outside the subgroup
+pub fn g1_from_uncompressed_bytes(bytes: &[u8; 96]) -> Result<G1Point, ByteConversionError> {
+    let flags = bytes[0] >> 5;
+    if flags & 0b100 != 0 {
+        return Err(ByteConversionError::InvalidValue);
+    }
+    if flags & 0b010 != 0 {
+        // infinity: no sort flag and nothing but zeros after the flags
+        return if flags == 0b010 && bytes[0] & 0x1f == 0 && bytes[1..].iter().all(|b| *b == 0) {
+            Ok(G1Point::neutral_element())
+        } else {
+            Err(ByteConversionError::InvalidValue)
+        };
+    }
+    if flags != 0 {
+        return Err(ByteConversionError::InvalidValue);
+    }
+
+    let coordinate = |chunk: &[u8]| {
+        let value = U384::from_bytes_be(chunk)?;
+        if value >= BLS12381FieldModulus::MODULUS {
+            return Err(ByteConversionError::InvalidValue);
+        }
+        Ok(BLS12381FieldElement::new(value))
+    };
+    let x = coordinate(&bytes[..48])?;
+    let y = coordinate(&bytes[48..])?;
+
+    let point = G1Point::from_affine(x, y).map_err(|_| ByteConversionError::InvalidValue)?;
+    check_point_is_in_subgroup(&point)
+        .then_some(point)
+        .ok_or(ByteConversionError::PointNotInSubgroup)
+}
+
+/// An SRS in struct-of-arrays layout: the projective coordinates of point `i` are
+/// `x[i]`, `y[i]`, `z[i]`, each coordinate in its own contiguous buffer, as GPU MSM
+/// kernels expect
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoaSrs {
+    pub x: Vec<BLS12381FieldElement>,
+    pub y: Vec<BLS12381FieldElement>,
+    pub z: Vec<BLS12381FieldElement>,
+}
+
+/// Split the projective coordinates of `points` into a [`SoaSrs`], without normalizing
+pub fn srs_to_soa(points: &[G1Point]) -> SoaSrs {
+    let mut soa = SoaSrs {
+        x: Vec::with_capacity(points.len()),
+        y: Vec::with_capacity(points.len()),
+        z: Vec::with_capacity(points.len()),
+    };
+    for point in points {
+        let [x, y, z] = point.coordinates().clone();
+        soa.x.push(x);
+        soa.y.push(y);
+        soa.z.push(z);
+    }
+    soa
+}
+
+/// Reassemble the points of a [`SoaSrs`]. Panics if the buffers differ in length
+pub fn soa_to_srs(soa: &SoaSrs) -> Vec<G1Point> {
+    assert!(soa.x.len() == soa.y.len() && soa.y.len() == soa.z.len());
+    soa.x
+        .iter()
+        .zip(&soa.y)
+        .zip(&soa.z)
+        .map(|((x, y), z)| G1Point::new([x.clone(), y.clone(), z.clone()]))
+        .collect()
+}
+
+/// Run `f` inside a dedicated rayon pool with `num_threads` workers instead of the
+/// global pool. Everything parallel in this crate (SRS generation, the G1 FFT,
+/// commitments) picks up the pool installed around it, e.g.
+/// `with_thread_pool(4, || prover.commit_lagrange(&witness, &srs))`
+pub fn with_thread_pool<R, F>(num_threads: usize, f: F) -> Result<R, rayon::ThreadPoolBuildError>
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()?;
+    Ok(pool.install(f))
 }
 
 #[cfg(test)]
 mod tests {
-    use lambdaworks_math::{fft::polynomial::FFTPoly, msm::naive::msm, polynomial::Polynomial};
+    use lambdaworks_math::{fft::polynomial::FFTPoly, polynomial::Polynomial};
 
-    use crate::srs::generate_srs;
+    use crate::{
+        srs::{generate_srs, generate_srs_g2},
+        test_fixtures::{small_srs, SMALL_SRS_TAU},
+    };
 
     use super::*;
 
+    #[test]
+    fn test_scalar_mul() {
+        let tau = FrElement::from(SMALL_SRS_TAU);
+        assert_eq!(scalar_mul(&g1_generator(), &tau), small_srs()[1]);
+        assert_eq!(
+            scalar_mul(&small_srs()[1], &FrElement::zero()),
+            G1Point::neutral_element()
+        );
+    }
+
+    #[test]
+    fn test_is_reduced() {
+        let modulus = FrConfig::MODULUS;
+
+        assert!(is_reduced(&U256::from_u64(0)));
+        assert!(is_reduced(&(modulus - U256::from_u64(1))));
+        assert!(!is_reduced(&modulus));
+        assert!(!is_reduced(&U256::from_limbs([u64::MAX; 4])));
+
+        // non-canonical limbs collapse to the canonical element
+        let value = U256::from_u64(5);
+        assert_eq!(FrElement::new(modulus + value), FrElement::new(value));
+
+        assert!(random_field_elements(64)
+            .iter()
+            .all(|e| is_reduced(&e.representative())));
+    }
+
+    #[test]
+    fn test_seeded_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (
+                random_fr_with_rng(&mut rng),
+                random_field_elements_with_rng(&mut rng, 4),
+                random_poly_with_rng(&mut rng, 3),
+            )
+        };
+
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+    }
+
+    #[test]
+    fn test_fill_random_field_elements() {
+        let mut buf = vec![FrElement::zero(); 64];
+        fill_random_field_elements(&mut buf);
+        assert!(buf.iter().all(|e| *e != FrElement::zero()));
+
+        let previous = buf.clone();
+        fill_random_field_elements(&mut buf);
+        assert!(buf.iter().zip(&previous).all(|(a, b)| a != b));
+    }
+
+    #[test]
+    fn test_random_poly_bounded() {
+        let bound = 5;
+        let polynomial = random_poly_bounded(255, bound);
+
+        assert!(polynomial.coeff_len() <= 256);
+        assert!(polynomial
+            .coefficients()
+            .iter()
+            .all(|c| c.representative() < U256::from_u64(bound)));
+        assert!(random_poly_bounded(7, 1) == Polynomial::zero());
+    }
+
+    #[test]
+    fn test_monomial() {
+        let srs = small_srs();
+        // the constant 1 over the whole SRS domain, so the product is the witness itself
+        let domain = Domain::for_coeff_len(srs.len(), 1).unwrap();
+        let prover =
+            Prover::new_with_domain(Polynomial::new(&[FrElement::one()]), &domain).unwrap();
+
+        for k in 0..srs.len() {
+            let witness = monomial(k, srs.len());
+            let cs = witness
+                .coefficients()
+                .iter()
+                .map(|c| c.representative())
+                .collect::<Vec<_>>();
+            assert_eq!(msm(&cs, &srs[..cs.len()]).unwrap(), srs[k]);
+            assert_eq!(prover.commit_polynomial(&witness, srs).unwrap(), srs[k]);
+        }
+    }
+
+    #[test]
+    fn test_all_distinct() {
+        assert!(all_distinct(&[]));
+        assert!(all_distinct(&random_field_elements(32)));
+
+        let modulus_plus_two = FrElement::new(FrConfig::MODULUS + U256::from_u64(2));
+        assert!(!all_distinct(&[
+            FrElement::from(2),
+            FrElement::from(7),
+            modulus_plus_two
+        ]));
+    }
+
+    #[test]
+    fn test_fiat_shamir_challenge() {
+        let commitment = small_srs()[3].clone();
+        let challenge = fiat_shamir_challenge(b"seed", &commitment, 4);
+
+        assert_eq!(challenge, fiat_shamir_challenge(b"seed", &commitment, 4));
+        assert_ne!(challenge, fiat_shamir_challenge(b"other", &commitment, 4));
+        assert_ne!(
+            challenge,
+            fiat_shamir_challenge(b"seed", &small_srs()[4], 4)
+        );
+        assert_ne!(challenge.pow(16u64), FrElement::one());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the 2-adicity")]
+    fn test_fiat_shamir_challenge_order_too_large() {
+        fiat_shamir_challenge(b"seed", &small_srs()[1], 64);
+    }
+
+    #[test]
+    fn test_random_non_domain_point() {
+        for order in [0, 3, 17] {
+            let point = random_non_domain_point(order);
+            assert_ne!(point.pow(1_u64 << order), FrElement::one());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the 2-adicity")]
+    fn test_random_non_domain_point_order_too_large() {
+        random_non_domain_point(max_fft_domain_log2() + 1);
+    }
+
+    #[test]
+    fn test_mul_evaluations() {
+        let a = random_poly(3);
+        let b = random_poly(3);
+        let c = random_poly(3);
+
+        // two degree 3 polynomials: the product fits a domain of 8
+        let product = mul_evaluations(
+            &a.evaluate_fft(2, None).unwrap(),
+            &b.evaluate_fft(2, None).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            product,
+            a.mul_with_ref(&b).evaluate_fft(1, Some(8)).unwrap()
+        );
+
+        // chaining three needs a domain of 16
+        let product = [&b, &c]
+            .iter()
+            .fold(a.evaluate_fft(4, None).unwrap(), |acc, p| {
+                mul_evaluations(&acc, &p.evaluate_fft(4, None).unwrap()).unwrap()
+            });
+        assert_eq!(
+            product,
+            a.mul_with_ref(&b)
+                .mul_with_ref(&c)
+                .evaluate_fft(1, Some(16))
+                .unwrap()
+        );
+
+        assert!(mul_evaluations(&product, &product[1..]).is_err());
+    }
+
+    #[test]
+    fn test_sub_evaluations() {
+        let a = random_poly(7);
+        let b = random_poly(5);
+        let (a_eval, b_eval) = (
+            a.evaluate_fft(2, Some(8)).unwrap(),
+            b.evaluate_fft(2, Some(8)).unwrap(),
+        );
+
+        assert_eq!(
+            sub_evaluations(&a_eval, &b_eval).unwrap(),
+            (a - b).evaluate_fft(2, Some(8)).unwrap()
+        );
+        assert!(sub_evaluations(&a_eval, &b_eval[1..]).is_err());
+    }
+
+    #[test]
+    fn test_to_lagrange_basis_inverse_length() {
+        // The Lagrange basis sums to 1, so the scaled points must sum to the generator.
+        // This pins the 1/n scaling across domain sizes, including the trivial one
+        for order in 0..6 {
+            // generate_srs needs at least two points, truncate for the trivial domain
+            let srs = generate_srs((1 << order).max(2), FrElement::from(42));
+            let srs = &srs[..1 << order];
+            let sum = to_lagrange_basis(srs)
+                .unwrap()
+                .iter()
+                .fold(G1Point::neutral_element(), |acc, p| acc.operate_with(p));
+
+            assert_eq!(sum, srs[0]);
+        }
+    }
+
+    #[test]
+    fn test_detect_basis() {
+        let srs = small_srs();
+        let g2 = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU));
+
+        assert_eq!(detect_basis(srs, &g2), Basis::Monomial);
+        assert_eq!(
+            detect_basis(&to_lagrange_basis(srs).unwrap(), &g2),
+            Basis::Lagrange
+        );
+
+        // an SRS for a different tau matches neither
+        let other = generate_srs(8, FrElement::from(SMALL_SRS_TAU + 1));
+        assert_eq!(detect_basis(&other, &g2), Basis::Unknown);
+    }
+
+    #[test]
+    fn test_lagrange_commitment() {
+        let lagrange_srs = to_lagrange_basis(small_srs()).unwrap();
+
+        for i in 0..lagrange_srs.len() {
+            assert!(verify_lagrange_commitment(&lagrange_srs, i).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_relate_commitments() {
+        let srs = generate_srs(16, FrElement::from(SMALL_SRS_TAU));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+
+        assert!(relate_commitments(&prover, &witness, &srs, &lagrange_srs).unwrap());
+        assert!(relate_commitments(&prover, &Polynomial::zero(), &srs, &lagrange_srs).unwrap());
+
+        // a Lagrange SRS for another tau commits differently
+        let other =
+            to_lagrange_basis(&generate_srs(16, FrElement::from(SMALL_SRS_TAU + 1))).unwrap();
+        assert!(!relate_commitments(&prover, &witness, &srs, &other).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate_commitments() {
+        let commitments = &small_srs()[..3];
+        let scalars = [FrElement::from(2), FrElement::from(5), FrElement::from(0)];
+
+        let manual = commitments[0]
+            .operate_with(&commitments[0])
+            .operate_with(&commitments[1].operate_with_self(5u64));
+        assert_eq!(
+            aggregate_commitments(commitments, &scalars).unwrap(),
+            manual
+        );
+
+        assert_eq!(
+            aggregate_commitments(&[], &[]).unwrap(),
+            G1Point::neutral_element()
+        );
+        assert!(aggregate_commitments(commitments, &scalars[..2]).is_err());
+    }
+
+    #[test]
+    fn test_srs_sum() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        // the constant 1 prover turns the witness evaluations into the MSM scalars
+        let prover = Prover::new_with_blowup(Polynomial::new(&[FrElement::one()]), 16).unwrap();
+
+        assert_eq!(
+            srs_sum(&lagrange_srs),
+            prover
+                .commit_lagrange(&Polynomial::new(&[FrElement::one()]), &lagrange_srs)
+                .unwrap()
+        );
+        assert_eq!(srs_sum(&[]), G1Point::neutral_element());
+    }
+
+    #[test]
+    fn test_add_constant_to_commitment() {
+        let srs = small_srs();
+        let commit = |poly: &Polynomial<FrElement>| {
+            let cs = poly
+                .coefficients()
+                .iter()
+                .map(|c| c.representative())
+                .collect::<Vec<_>>();
+            msm(&cs, &srs[..cs.len()]).unwrap()
+        };
+
+        let poly = random_poly(srs.len() - 1);
+        let constant = random_fr();
+        let shifted = poly.clone() + Polynomial::new(std::slice::from_ref(&constant));
+
+        assert_eq!(
+            add_constant_to_commitment(&commit(&poly), &constant, &srs[0]),
+            commit(&shifted)
+        );
+    }
+
+    #[test]
+    fn test_scale_commitment() {
+        let lagrange_srs = to_lagrange_basis(&generate_srs(16, FrElement::from(42))).unwrap();
+        let prover = Prover::new(random_poly(7)).unwrap();
+        let witness = random_poly(7);
+        let k = random_fr();
+
+        let scaled_witness = Polynomial::new(
+            &witness
+                .coefficients()
+                .iter()
+                .map(|c| c * &k)
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            scale_commitment(
+                &prover.commit_lagrange(&witness, &lagrange_srs).unwrap(),
+                &k
+            ),
+            prover
+                .commit_lagrange(&scaled_witness, &lagrange_srs)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_decimal_string() {
+        assert_eq!(to_decimal_string(&U384::from_u64(0)), "0");
+        assert_eq!(
+            to_decimal_string(&U384::from_u64(u64::MAX)),
+            u64::MAX.to_string()
+        );
+        // 2^64 * 10^19 + 7 straddles a limb and a decimal chunk boundary
+        let value = U384::from_u128(1 << 64) * U384::from_u64(10_000_000_000_000_000_000)
+            + U384::from_u64(7);
+        assert_eq!(
+            to_decimal_string(&value),
+            "184467440737095516160000000000000000007"
+        );
+    }
+
+    #[test]
+    fn test_srs_soa_roundtrip() {
+        // a non-normalized point and infinity survive with their exact coordinates
+        let mut points = small_srs().to_vec();
+        points.push(points[1].operate_with(&points[2]));
+        points.push(G1Point::neutral_element());
+
+        let soa = srs_to_soa(&points);
+        assert_eq!(soa.x.len(), points.len());
+        let roundtrip = soa_to_srs(&soa);
+        for (a, b) in roundtrip.iter().zip(&points) {
+            assert_eq!(a.coordinates(), b.coordinates());
+        }
+    }
+
+    #[test]
+    fn test_g1_uncompressed_bytes() {
+        use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::compression::{
+            compress_g1_point, decompress_g1_point,
+        };
+
+        let mut points = small_srs().to_vec();
+        points.push(G1Point::neutral_element());
+
+        for point in &points {
+            let bytes = g1_to_uncompressed_bytes(point);
+            let decoded = g1_from_uncompressed_bytes(&bytes).unwrap();
+            assert_eq!(&decoded, point);
+
+            // the compressed form carries the same big-endian x
+            let compressed: [u8; 48] = compress_g1_point(point).try_into().unwrap();
+            assert_eq!(compressed[0] & 0x1f, bytes[0] & 0x1f);
+            assert_eq!(compressed[1..], bytes[1..48]);
+            assert_eq!(
+                decompress_g1_point(&mut compressed.clone()).unwrap(),
+                decoded
+            );
+        }
+
+        // compression flag set
+        let mut bytes = g1_to_uncompressed_bytes(&points[1]);
+        bytes[0] |= 1 << 7;
+        assert!(g1_from_uncompressed_bytes(&bytes).is_err());
+
+        // not on the curve
+        let mut bytes = g1_to_uncompressed_bytes(&points[1]);
+        bytes[95] ^= 1;
+        assert!(g1_from_uncompressed_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_with_thread_pool() {
+        let polynomial = random_poly(7);
+        let witness = random_poly(7);
+        let prover = Prover::new(polynomial).unwrap();
+        let srs = generate_srs(16, FrElement::from(SMALL_SRS_TAU));
+        let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+
+        let (threads, pooled) = with_thread_pool(2, || {
+            (
+                rayon::current_num_threads(),
+                prover.commit_lagrange(&witness, &lagrange_srs).unwrap(),
+            )
+        })
+        .unwrap();
+
+        assert_eq!(threads, 2);
+        assert_eq!(pooled, prover.commit_polynomial(&witness, &srs).unwrap());
+    }
+
+    #[test]
+    fn test_interpolate_from_domain_evals() {
+        // a known polynomial from all of its evaluations
+        let polynomial = random_poly(7);
+        let pairs = polynomial
+            .evaluate_fft(1, None)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            interpolate_from_domain_evals(&pairs, 3).unwrap(),
+            polynomial
+        );
+
+        // sparse evaluations: only indices 1 and 6 are non-zero
+        let sparse = [(1, FrElement::from(4)), (6, FrElement::from(9))];
+        let interpolated = interpolate_from_domain_evals(&sparse, 3).unwrap();
+        let evaluations = interpolated.evaluate_fft(1, Some(8)).unwrap();
+        for (i, e) in evaluations.iter().enumerate() {
+            match sparse.iter().find(|(j, _)| *j == i) {
+                Some((_, value)) => assert_eq!(e, value),
+                None => assert_eq!(*e, FrElement::zero()),
+            }
+        }
+
+        assert!(interpolate_from_domain_evals(&[(8, FrElement::one())], 3).is_err());
+    }
+
+    #[test]
+    fn test_max_fft_domain_log2() {
+        // BLS12-381 scalar field: r - 1 = 2^32 * odd
+        assert_eq!(max_fft_domain_log2(), 32);
+    }
+
     #[test]
     fn test_to_lagrange_basis() {
-        let srs = generate_srs(8, FrElement::from(42));
+        let srs = small_srs();
 
         let coefficients = vec![
             FrElement::from(6),
@@ -178,7 +1250,7 @@ mod tests {
             .iter()
             .map(|c| c.representative())
             .collect::<Vec<_>>();
-        let commitment1 = msm(&cs, &srs).unwrap();
+        let commitment1 = msm(&cs, srs).unwrap();
 
         // 2. Compute the polynomial commitment using SRS in Lagrange basis, and polynomial evaluations
         // C = e0 [l0 * G] + e1 [l1 * G] + ... + en [ln * G]
@@ -190,9 +1262,166 @@ mod tests {
             .iter()
             .map(|e| e.representative())
             .collect::<Vec<_>>();
-        let lagrange_basis = to_lagrange_basis(&srs).unwrap();
+        let lagrange_basis = to_lagrange_basis(srs).unwrap();
         let commitment2 = msm(&evaluations, &lagrange_basis).unwrap();
 
         assert!(commitment1 == commitment2);
     }
+
+    #[test]
+    fn test_lagrange_srs_downsample() {
+        let srs = generate_srs(16, FrElement::from(SMALL_SRS_TAU));
+        let downsampled = lagrange_srs_downsample(&srs, 3).unwrap();
+        assert_eq!(downsampled.len(), 8);
+        assert_ne!(downsampled, to_lagrange_basis(&srs).unwrap()[..8]);
+
+        // commits correctly on the size 8 domain (blowup 2 over 4 coefficients)
+        let prover = Prover::new(random_poly(3)).unwrap();
+        let witness = random_poly(3);
+        assert_eq!(
+            prover.commit_lagrange(&witness, &downsampled).unwrap(),
+            prover.commit_polynomial(&witness, &srs).unwrap()
+        );
+
+        assert!(lagrange_srs_downsample(&srs, 5).is_err());
+    }
+
+    #[test]
+    fn test_fft_g_with_domain() {
+        let polynomial = random_poly(7);
+        let g1 = g1_generator();
+        let points = polynomial
+            .coefficients()
+            .iter()
+            .map(|c| scalar_mul(&g1, c))
+            .collect::<Vec<_>>();
+
+        // the point FFT evaluates the polynomial "in the exponent"
+        for domain in [
+            Domain::new(3, 1).unwrap(),
+            Domain::new_coset(3, 1, FrElement::from(7)).unwrap(),
+        ] {
+            let expected = domain
+                .evaluate(&polynomial)
+                .unwrap()
+                .iter()
+                .map(|e| scalar_mul(&g1, e))
+                .collect::<Vec<_>>();
+            assert_eq!(fft_g_with_domain(&points, &domain).unwrap(), expected);
+        }
+
+        // and inverts the Lagrange transform
+        let domain = Domain::new(3, 1).unwrap();
+        let lagrange = to_lagrange_basis(small_srs()).unwrap();
+        assert_eq!(fft_g_with_domain(&lagrange, &domain).unwrap(), small_srs());
+
+        assert!(fft_g_with_domain(&points[..4], &domain).is_err());
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_validate_lagrange_roundtrip() {
+        assert!(to_lagrange_basis(small_srs()).is_ok());
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    #[should_panic(expected = "roundtrip does not recover the input")]
+    fn test_validate_lagrange_roundtrip_broken_permutation() {
+        // skipping the bit reversal leaves the basis out of order
+        let _ = to_lagrange_basis_with_permutation(small_srs(), |_| {});
+    }
+
+    #[test]
+    fn test_to_lagrange_basis_coset_chain() {
+        let srs = small_srs();
+        let (g, eta) = (FrElement::from(7), FrElement::from(3));
+        let offsets = [g.clone(), &g * &eta, &g * eta.square()];
+
+        let chain = to_lagrange_basis_coset_chain(srs, &offsets).unwrap();
+        assert_eq!(chain.len(), offsets.len());
+        for (lagrange, offset) in chain.iter().zip(offsets) {
+            let domain = Domain::new_coset(3, 1, offset).unwrap();
+            assert_eq!(
+                lagrange,
+                &to_lagrange_basis_with_domain(srs, &domain).unwrap()
+            );
+        }
+
+        assert!(to_lagrange_basis_coset_chain(srs, &[FrElement::zero()]).is_err());
+        // a length that is not a power of two fails before the transform
+        assert!(to_lagrange_basis_coset_chain(&srs[..6], &[FrElement::one()]).is_err());
+    }
+
+    #[test]
+    fn test_to_lagrange_basis_ordering() {
+        for log_size in 1..=7 {
+            let n = 1 << log_size;
+            let srs = generate_srs(n, FrElement::from(SMALL_SRS_TAU));
+            let lagrange_srs = to_lagrange_basis(&srs).unwrap();
+
+            let polynomial = random_poly(n - 1);
+            let cs = polynomial
+                .coefficients()
+                .iter()
+                .map(|c| c.representative())
+                .collect::<Vec<_>>();
+            let evaluations = polynomial
+                .evaluate_fft(1, Some(n))
+                .unwrap()
+                .iter()
+                .map(|e| e.representative())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                msm(&cs, &srs[..cs.len()]).unwrap(),
+                msm(&evaluations, &lagrange_srs).unwrap(),
+                "ordering mismatch for n = {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_lagrange_basis_negacyclic() {
+        let n = 8;
+        let srs = generate_srs(n, FrElement::from(42));
+        let negacyclic = to_lagrange_basis_negacyclic(&srs).unwrap();
+
+        // Direct reference: L_j * G = (1/n) * sum_i (psi * w^j)^-i * srs[i]
+        let psi = FrField::get_primitive_root_of_unity::<FrField>(4).unwrap();
+        let w = psi.square();
+        let n_inv = FrElement::from(n as u64).inv().unwrap();
+        let reference = (0..n)
+            .map(|j| {
+                let x_inv = (&psi * w.pow(j as u64)).inv().unwrap();
+                let scalars = (0..n)
+                    .map(|i| (x_inv.pow(i as u64) * &n_inv).representative())
+                    .collect::<Vec<_>>();
+                msm(&scalars, &srs).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(negacyclic, reference);
+
+        // Committing the negacyclic evaluations matches the coefficient commitment
+        let polynomial = random_poly(n - 1);
+        let cs = polynomial
+            .coefficients()
+            .iter()
+            .map(|c| c.representative())
+            .collect::<Vec<_>>();
+        let evaluations = (0..n)
+            .map(|j| {
+                polynomial
+                    .evaluate(&(&psi * w.pow(j as u64)))
+                    .representative()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            msm(&cs, &srs[..cs.len()]).unwrap(),
+            msm(&evaluations, &negacyclic).unwrap()
+        );
+    }
 }
@@ -0,0 +1,241 @@
+use lambdaworks_math::{
+    cyclic_group::IsGroup,
+    elliptic_curve::{
+        short_weierstrass::curves::bls12_381::{
+            default_types::{FrElement, FrField},
+            pairing::BLS12381AtePairing,
+        },
+        traits::IsPairing,
+    },
+    fft::errors::FFTError,
+    field::{element::FieldElement, traits::IsFFTField},
+    msm::naive::msm,
+};
+use rayon::prelude::*;
+
+use crate::{
+    g1_generator,
+    utils::{random_fr, scalar_mul},
+    G1Point, G2Point,
+};
+
+/// A KZG opening claim: `proof` attests that the polynomial committed in
+/// `commitment` takes `value` at `point`
+#[derive(Clone, Debug)]
+pub struct OpeningItem {
+    pub commitment: G1Point,
+    pub point: FrElement,
+    pub value: FrElement,
+    pub proof: G1Point,
+}
+
+impl OpeningItem {
+    /// The G1 side of the pairing equation: C - y * G + z * proof
+    fn lhs(&self) -> G1Point {
+        let g1 = g1_generator();
+
+        self.commitment
+            .operate_with(&scalar_mul(&g1, &self.value).neg())
+            .operate_with(&scalar_mul(&self.proof, &self.point))
+    }
+}
+
+/// Verify a single opening against the G2 SRS `[G2, tau * G2]`, checking
+/// e(C - y * G + z * proof, G2) == e(proof, tau * G2)
+pub fn verify(item: &OpeningItem, srs_g2: &[G2Point]) -> bool {
+    verify_batch(std::slice::from_ref(item), srs_g2)
+}
+
+/// Verify many openings with a single pairing equation.
+///
+/// Every claim is folded with a fresh random scalar `r_i`:
+///
+/// e(sum_i r_i * (C_i - y_i * G + z_i * proof_i), G2) == e(sum_i r_i * proof_i, tau * G2)
+///
+/// If any claim is false the folded equation holds only when the random scalars
+/// happen to cancel the error, which happens with probability at most 1/|Fr|
+/// (the scalars are drawn after the items are fixed). An empty batch is accepted.
+pub fn verify_batch(items: &[OpeningItem], srs_g2: &[G2Point]) -> bool {
+    if srs_g2.len() < 2 {
+        return false;
+    }
+
+    let (lhs, proofs) = items.iter().fold(
+        (G1Point::neutral_element(), G1Point::neutral_element()),
+        |(lhs, proofs), item| {
+            let r = random_fr();
+            (
+                lhs.operate_with(&scalar_mul(&item.lhs(), &r)),
+                proofs.operate_with(&scalar_mul(&item.proof, &r)),
+            )
+        },
+    );
+
+    let pairing =
+        BLS12381AtePairing::compute_batch(&[(&lhs, &srs_g2[0]), (&proofs.neg(), &srs_g2[1])]);
+
+    pairing == FieldElement::one()
+}
+
+/// Check that a G1 SRS and a G2 SRS `[G2, tau * G2]` encode the same tau:
+/// e(tau * G, G2) == e(G, tau * G2). Only the first two points of each are used
+pub fn verify_srs_pair(g1: &[G1Point], g2: &[G2Point]) -> bool {
+    if g1.len() < 2 || g2.len() < 2 {
+        return false;
+    }
+
+    let pairing = BLS12381AtePairing::compute_batch(&[(&g1[1], &g2[0]), (&g1[0].neg(), &g2[1])]);
+
+    pairing == FieldElement::one()
+}
+
+/// G1 images of the Lagrange basis of the size `n` subgroup at a public `point`:
+/// `[L_0(z) * G, ..., L_{n-1}(z) * G]` with `L_i(z) = w^i (z^n - 1) / (n (z - w^i))`.
+/// `point` must lie outside the subgroup
+pub fn lagrange_basis_at(point: &FrElement, n: usize) -> Result<Vec<G1Point>, FFTError> {
+    let omega = FrField::get_primitive_root_of_unity::<FrField>(n.trailing_zeros().into())?;
+    let g1 = g1_generator();
+
+    let roots = (0..n as u64).map(|i| omega.pow(i)).collect::<Vec<_>>();
+    let mut denominators = roots.iter().map(|w_i| point - w_i).collect::<Vec<_>>();
+    FrElement::inplace_batch_inverse(&mut denominators).map_err(|_| FFTError::InputError(n))?;
+
+    let scale = (point.pow(n as u64) - FrElement::one()) * FrElement::from(n as u64).inv()?;
+    Ok(roots
+        .par_iter()
+        .zip(&denominators)
+        .map(|(w_i, d)| scalar_mul(&g1, &(w_i * d * &scale)))
+        .collect())
+}
+
+/// Pairing-free consistency check of a Lagrange commitment against a claimed value
+/// at a public point, with `lagrange_at_point` from [`lagrange_basis_at`]. Checks in
+/// G1 only that `commitment == sum_i e_i * lagrange_srs[i]` and
+/// `value * G == sum_i e_i * L_i(z) * G`.
+///
+/// Limits: the verifier needs every evaluation `e_i`, so this is a structural check
+/// of the pipeline (SRS, basis, commitment and value agree), not a succinct proof,
+/// and it gives none of the KZG guarantees. Use [`verify`] with a G2 SRS for that
+pub fn verify_without_pairing(
+    commitment: &G1Point,
+    evaluations: &[FrElement],
+    lagrange_srs: &[G1Point],
+    lagrange_at_point: &[G1Point],
+    value: &FrElement,
+) -> bool {
+    if evaluations.len() != lagrange_srs.len() || evaluations.len() != lagrange_at_point.len() {
+        return false;
+    }
+
+    let scalars = evaluations
+        .iter()
+        .map(|e| e.representative())
+        .collect::<Vec<_>>();
+    let g1 = g1_generator();
+
+    msm(&scalars, lagrange_srs).is_ok_and(|c| c == *commitment)
+        && msm(&scalars, lagrange_at_point).is_ok_and(|v| v == scalar_mul(&g1, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use lambdaworks_math::fft::polynomial::FFTPoly;
+
+    use crate::{
+        prover::open,
+        srs::generate_srs_g2,
+        test_fixtures::{small_srs, SMALL_SRS_TAU},
+        utils::{random_non_domain_point, random_poly, to_lagrange_basis},
+    };
+
+    use super::*;
+
+    fn opening_items(count: usize) -> (Vec<OpeningItem>, Vec<G2Point>) {
+        let srs = small_srs();
+
+        let items = (0..count)
+            .map(|i| {
+                let poly = random_poly(7);
+                let cs = poly
+                    .coefficients()
+                    .iter()
+                    .map(|c| c.representative())
+                    .collect::<Vec<_>>();
+                let commitment = msm(&cs, &srs[..cs.len()]).unwrap();
+
+                let point = FrElement::from(i as u64 + 3);
+                let (value, proof) = open(&poly, &point, srs).unwrap();
+
+                OpeningItem {
+                    commitment,
+                    point,
+                    value,
+                    proof,
+                }
+            })
+            .collect();
+
+        (items, generate_srs_g2(&FrElement::from(SMALL_SRS_TAU)))
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let (mut items, srs_g2) = opening_items(3);
+
+        assert!(verify(&items[0], &srs_g2));
+        assert!(verify_batch(&items, &srs_g2));
+
+        items[1].value = &items[1].value + FrElement::one();
+
+        assert!(!verify(&items[1], &srs_g2));
+        assert!(!verify_batch(&items, &srs_g2));
+    }
+
+    #[test]
+    fn test_verify_srs_pair() {
+        let g2 = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU));
+        assert!(verify_srs_pair(small_srs(), &g2));
+
+        let other_g2 = generate_srs_g2(&FrElement::from(SMALL_SRS_TAU + 1));
+        assert!(!verify_srs_pair(small_srs(), &other_g2));
+        assert!(!verify_srs_pair(&small_srs()[..1], &g2));
+    }
+
+    #[test]
+    fn test_verify_without_pairing() {
+        let lagrange_srs = to_lagrange_basis(small_srs()).unwrap();
+        let poly = random_poly(7);
+        let evaluations = poly.evaluate_fft(1, None).unwrap();
+        let scalars = evaluations
+            .iter()
+            .map(|e| e.representative())
+            .collect::<Vec<_>>();
+        let commitment = msm(&scalars, &lagrange_srs).unwrap();
+
+        let point = random_non_domain_point(3);
+        let lagrange_at_point = lagrange_basis_at(&point, 8).unwrap();
+        let value = poly.evaluate(&point);
+
+        assert!(verify_without_pairing(
+            &commitment,
+            &evaluations,
+            &lagrange_srs,
+            &lagrange_at_point,
+            &value
+        ));
+        assert!(!verify_without_pairing(
+            &commitment,
+            &evaluations,
+            &lagrange_srs,
+            &lagrange_at_point,
+            &(value + FrElement::one())
+        ));
+        assert!(!verify_without_pairing(
+            &commitment.operate_with(&commitment),
+            &evaluations,
+            &lagrange_srs,
+            &lagrange_at_point,
+            &poly.evaluate(&point)
+        ));
+    }
+}